@@ -1,18 +1,81 @@
-use std::{fs::File, io};
+use std::{
+    f64::consts::PI,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
 
 use crate::{
-    geometry::intersectable::Intersectable,
-    image::{Color, ppm},
+    geometry::intersectable::{HitRecord, Intersectable},
+    image::{Color, Framebuffer, color},
     interval::Interval,
-    math::{Ray, Vector3f},
-    shapes::shapes::Shapes,
+    materials::material::Material,
+    math::{Point3f, Ray, Vector3f, utils},
+    scene::Scene,
 };
-use std::io::Write;
 
-fn ray_color(ray: &Ray) -> Color<f64> {
-    let unit_direction = ray.direction.normalize();
-    let a = 0.5 * (unit_direction.y + 1.0);
-    (Color::new(1.0, 1.0, 1.0) * (1.0 - a)) + (Color::new(0.5, 0.7, 1.0) * a)
+/// Smallest ray distance counted as a hit, avoids shadow acne from a scattered
+/// ray hitting the surface it left due to floating point error
+const T_MIN: f64 = 0.001;
+
+/// Light arriving straight from the point lights at a diffuse hit point,
+/// lights blocked by another object cast a shadow
+fn direct_light(hit: &HitRecord, albedo: Color<f64>, scene: &Scene) -> Color<f64> {
+    let mut light = color::BLACK;
+
+    for point_light in scene.lights() {
+        // the shadow ray reaches the light at t = 1
+        let to_light = point_light.position - hit.point;
+        let cos_theta = hit.normal.dot(&to_light.normalize());
+        if cos_theta <= 0.0 {
+            continue;
+        }
+
+        let shadow_ray = Ray::new(hit.point, to_light).with_time(hit.ray.time);
+        if scene
+            .intersect(&shadow_ray, Interval::new(T_MIN, 1.0))
+            .is_some()
+        {
+            continue;
+        }
+
+        light = light + point_light.intensity * (cos_theta / (PI * to_light.norm_squared()));
+    }
+
+    albedo * light
+}
+
+/// Follow the ray as it scatters around the scene, up to depth bounces
+fn ray_color(ray: &Ray, depth: u32, scene: &Scene) -> Color<f64> {
+    if depth == 0 {
+        return color::BLACK;
+    }
+
+    let Some(hit) = scene.intersect(ray, Interval::new(T_MIN, f64::INFINITY)) else {
+        return scene.background().color(ray);
+    };
+
+    // area lights are only found by rays that happen to hit them
+    let emitted = hit.material.emitted(&hit);
+    let direct = match hit.material.diffuse(&hit) {
+        Some(albedo) => direct_light(&hit, albedo, scene),
+        None => color::BLACK,
+    };
+
+    match hit.material.scatter(ray, &hit) {
+        Some(scatter) => {
+            emitted + direct + scatter.attenuation * ray_color(&scatter.scattered, depth - 1, scene)
+        }
+        None => emitted + direct,
+    }
+}
+
+/// True when no camera orientation follows from the view, because look_from
+/// and look_at coincide or vup is zero or parallel to the view direction
+pub fn is_degenerate_view(look_from: &Point3f, look_at: &Point3f, vup: &Vector3f) -> bool {
+    let direction = *look_at - *look_from;
+    utils::near_zero_vector(&direction)
+        || utils::near_zero_vector(vup)
+        || utils::near_zero_vector(&vup.normalize().cross(&direction.normalize()))
 }
 
 pub struct Camera {
@@ -22,71 +85,274 @@ pub struct Camera {
     image_width: u16,
     pixel_delta_u: Vector3f,
     pixel_delta_v: Vector3f,
+    /// maximum number of times a ray bounces before it stops gathering light
+    max_depth: u32,
+    /// number of random rays averaged for each pixel
+    samples_per_pixel: u32,
+    /// point the camera looks from
+    look_from: Point3f,
+    /// point the camera looks at
+    look_at: Point3f,
+    /// camera relative "up" direction, rotating it rolls the camera
+    vup: Vector3f,
+    /// vertical field of view in degrees
+    vfov: f64,
+    /// variation angle in degrees of rays through each pixel, 0 disables depth of field
+    defocus_angle: f64,
+    /// distance from look_from to the plane of perfect focus, defaults to look_at
+    focus_dist: Option<f64>,
+    defocus_disk_u: Vector3f,
+    defocus_disk_v: Vector3f,
+    /// number of worker threads, defaults to the available parallelism
+    threads: Option<usize>,
+    /// base seed for the random sampling, same seed gives the same image
+    seed: u64,
+    /// rays are sent at random times between the shutter opening and closing,
+    /// an empty interval takes every ray at the same moment
+    shutter_open: f64,
+    shutter_close: f64,
 }
 
 impl Camera {
+    /// The image is at least one pixel wide and high
     pub fn new(aspect_ratio: f32, image_width: u16) -> Self {
+        let image_width = image_width.max(1);
         let image_height: u16 = (f32::from(image_width) / aspect_ratio).max(1.0) as u16;
 
-        // camera
-        let focal_length = 1.0;
-        let viewport_height = 2.0;
-        let viewport_width = viewport_height * (f64::from(image_width) / f64::from(image_height));
-        let camera_center = Vector3f::new(0.0, 0., 0.);
-        let viewport_u = Vector3f::new(viewport_width, 0., 0.);
-        let viewport_v = Vector3f::new(0., -viewport_height, 0.);
+        let mut camera = Self {
+            pixel00_loc: Vector3f::new(0., 0., 0.),
+            position: Vector3f::new(0., 0., 0.),
+            image_height,
+            image_width,
+            pixel_delta_u: Vector3f::new(0., 0., 0.),
+            pixel_delta_v: Vector3f::new(0., 0., 0.),
+            max_depth: 10,
+            samples_per_pixel: 1,
+            look_from: Vector3f::new(0., 0., 0.),
+            look_at: Vector3f::new(0., 0., -1.),
+            vup: Vector3f::new(0., 1., 0.),
+            vfov: 90.0,
+            defocus_angle: 0.0,
+            focus_dist: None,
+            defocus_disk_u: Vector3f::new(0., 0., 0.),
+            defocus_disk_v: Vector3f::new(0., 0., 0.),
+            threads: None,
+            seed: 0,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+        };
+        camera.initialize();
+        camera
+    }
+
+    /// Recompute the viewport from the camera placement
+    fn initialize(&mut self) {
+        self.position = self.look_from;
+
+        let focus_dist = self
+            .focus_dist
+            .unwrap_or_else(|| (self.look_from - self.look_at).norm());
+        let h = (self.vfov.to_radians() / 2.0).tan();
+        let viewport_height = 2.0 * h * focus_dist;
+        let viewport_width =
+            viewport_height * (f64::from(self.image_width) / f64::from(self.image_height));
+
+        // orthonormal basis, w points opposite the view direction
+        let w = (self.look_from - self.look_at).normalize();
+        let u = self.vup.cross(&w).normalize();
+        let v = w.cross(&u);
+
+        let viewport_u = u * viewport_width;
+        let viewport_v = -v * viewport_height;
         // Calculate the horizontal and vertical delta vectors from pixel to pixel.
-        let pixel_delta_u = viewport_u / f64::from(image_width);
-        let pixel_delta_v = viewport_v / f64::from(image_height);
+        self.pixel_delta_u = viewport_u / f64::from(self.image_width);
+        self.pixel_delta_v = viewport_v / f64::from(self.image_height);
 
         // Calculate the location of the upper left pixel.
-        let viewport_upper_left = camera_center
-            - Vector3f::new(0., 0., focal_length)
-            - viewport_u / 2.0
-            - viewport_v / 2.0;
-        let pixel00_loc = viewport_upper_left + (pixel_delta_u + pixel_delta_v) * 0.5;
-
-        Self {
-            pixel00_loc,
-            position: camera_center,
-            image_height,
-            image_width,
-            pixel_delta_u,
-            pixel_delta_v,
+        let viewport_upper_left =
+            self.position - w * focus_dist - viewport_u / 2.0 - viewport_v / 2.0;
+        self.pixel00_loc = viewport_upper_left + (self.pixel_delta_u + self.pixel_delta_v) * 0.5;
+
+        // Calculate the camera defocus disk basis vectors.
+        let defocus_radius = focus_dist * (self.defocus_angle / 2.0).to_radians().tan();
+        self.defocus_disk_u = u * defocus_radius;
+        self.defocus_disk_v = v * defocus_radius;
+    }
+
+    /// Place the camera at look_from aiming at look_at, vup sets the roll. The
+    /// two points must differ and vup must not be parallel to the view
+    /// direction, see `is_degenerate_view`, otherwise this panics
+    pub fn with_view(mut self, look_from: Point3f, look_at: Point3f, vup: Vector3f) -> Self {
+        assert!(
+            !is_degenerate_view(&look_from, &look_at, &vup),
+            "degenerate camera view from {look_from:?} to {look_at:?} with up {vup:?}"
+        );
+        self.look_from = look_from;
+        self.look_at = look_at;
+        self.vup = vup;
+        self.initialize();
+        self
+    }
+
+    /// Vertical field of view in degrees, panics outside of (0, 180)
+    pub fn with_vfov(mut self, vfov: f64) -> Self {
+        assert!(
+            vfov > 0.0 && vfov < 180.0,
+            "vertical field of view must be between 0 and 180 degrees, got {vfov}"
+        );
+        self.vfov = vfov;
+        self.initialize();
+        self
+    }
+
+    /// Thin lens depth of field, objects at focus_dist from the camera are sharp and
+    /// blur grows with defocus_angle (in degrees)
+    pub fn with_defocus(mut self, defocus_angle: f64, focus_dist: f64) -> Self {
+        self.defocus_angle = defocus_angle;
+        self.focus_dist = Some(focus_dist);
+        self.initialize();
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn with_samples_per_pixel(mut self, samples_per_pixel: u32) -> Self {
+        self.samples_per_pixel = samples_per_pixel.max(1);
+        self
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads.max(1));
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Motion blur, moving shapes are smeared over where they are between open
+    /// and close
+    pub fn with_shutter(mut self, open: f64, close: f64) -> Self {
+        self.shutter_open = open;
+        self.shutter_close = close.max(open);
+        self
+    }
+
+    /// Random moment while the shutter is open
+    fn shutter_time(&self) -> f64 {
+        if self.shutter_close > self.shutter_open {
+            utils::random_range(self.shutter_open, self.shutter_close)
+        } else {
+            self.shutter_open
         }
     }
 
-    pub fn render(&self, file: &mut File, scene: &Vec<Shapes>) -> io::Result<()> {
-        ppm::write_header(self.image_width, self.image_height, file)?;
-        let interval = Interval::new(0.0, f64::MAX);
+    /// Random point on the camera lens
+    fn defocus_disk_sample(&self) -> Point3f {
+        let p = Vector3f::random_in_unit_disk();
+        self.position + (self.defocus_disk_u * p.x) + (self.defocus_disk_v * p.y)
+    }
+
+    /// Ray from the lens through a random point in the square around pixel (i, j)
+    fn get_ray(&self, i: u16, j: u16) -> Ray {
+        let offset_u = utils::random_range(-0.5, 0.5);
+        let offset_v = utils::random_range(-0.5, 0.5);
+        let pixel_sample = self.pixel00_loc
+            + (self.pixel_delta_u * (f64::from(i) + offset_u))
+            + (self.pixel_delta_v * (f64::from(j) + offset_v));
 
-        for j in 0..self.image_height {
-            for i in 0..self.image_width {
-                let pixel_center = self.pixel00_loc
-                    + (self.pixel_delta_u * f64::from(i))
-                    + (self.pixel_delta_v * f64::from(j));
+        let ray_origin = if self.defocus_angle <= 0.0 {
+            self.position
+        } else {
+            self.defocus_disk_sample()
+        };
 
-                let ray_direction = pixel_center - self.position;
-                let ray = Ray::new(self.position, ray_direction);
+        Ray::new(ray_origin, pixel_sample - ray_origin).with_time(self.shutter_time())
+    }
 
-                let mut closest_so_far = interval.max;
-                let mut color = ray_color(&ray);
+    /// Render row j, the random generator is reseeded per row so the result does
+    /// not depend on which thread renders it
+    fn render_row(&self, j: u16, scene: &Scene) -> Vec<Color<f64>> {
+        utils::seed(self.seed ^ u64::from(j).wrapping_mul(0x9E37_79B9_7F4A_7C15));
 
-                for obj in scene.iter() {
-                    if let Some(hit_record) =
-                        obj.intersect(&ray, Interval::new(interval.min, closest_so_far))
-                    {
-                        closest_so_far = hit_record.time;
-                        color = hit_record.color
-                    };
+        (0..self.image_width)
+            .map(|i| {
+                let mut color = color::BLACK;
+                for _ in 0..self.samples_per_pixel {
+                    let ray = self.get_ray(i, j);
+                    color = color + ray_color(&ray, self.max_depth, scene);
                 }
+                color * (1.0 / f64::from(self.samples_per_pixel))
+            })
+            .collect()
+    }
+
+    pub fn render(&self, scene: &Scene) -> Framebuffer {
+        let height = usize::from(self.image_height);
+        let threads = self
+            .threads
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()))
+            .min(height);
+
+        // workers pull the next row to render until all rows are done
+        let next_row = AtomicUsize::new(0);
+        let mut framebuffer = Framebuffer::new(self.image_width, self.image_height);
+
+        let rows: Vec<(u16, Vec<Color<f64>>)> = thread::scope(|s| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    s.spawn(|| {
+                        let mut rows = Vec::new();
+                        loop {
+                            let j = next_row.fetch_add(1, Ordering::Relaxed);
+                            if j >= height {
+                                break rows;
+                            }
+                            let j = j as u16;
+                            rows.push((j, self.render_row(j, scene)));
+                        }
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("render thread panicked"))
+                .collect()
+        });
 
-                ppm::write_color(&color.as_u8(), file)?;
-            }
+        for (j, row) in rows {
+            framebuffer.set_row(j, &row);
         }
 
-        file.flush()?;
+        framebuffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_width_renders_one_pixel() {
+        let image = Camera::new(16.0 / 9.0, 0).render(&Scene::new());
+        assert_eq!((image.width(), image.height()), (1, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "degenerate camera view")]
+    fn degenerate_view_panics() {
+        let at = Point3f::new(0.0, 0.0, -1.0);
+        let _ = Camera::new(1.0, 8).with_view(at, at, Vector3f::new(0.0, 1.0, 0.0));
+    }
 
-        Ok(())
+    #[test]
+    #[should_panic(expected = "field of view")]
+    fn straight_angle_vfov_panics() {
+        let _ = Camera::new(1.0, 8).with_vfov(180.0);
     }
 }
@@ -0,0 +1,163 @@
+use crate::{
+    interval::{self, Interval},
+    math::{Point3f, Ray},
+};
+
+/// Axis aligned bounding box, one interval per axis
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub x: Interval,
+    pub y: Interval,
+    pub z: Interval,
+}
+
+pub const EMPTY: Aabb = Aabb::new(interval::EMPTY, interval::EMPTY, interval::EMPTY);
+pub const UNIVERSE: Aabb = Aabb::new(interval::UNIVERSE, interval::UNIVERSE, interval::UNIVERSE);
+/// Minimum thickness flat shapes pad their bounding box to along any axis
+pub const PADDING: f64 = 1e-4;
+
+impl Aabb {
+    pub const fn new(x: Interval, y: Interval, z: Interval) -> Self {
+        Self { x, y, z }
+    }
+
+    /// box with a and b as opposite corners
+    pub fn from_points(a: Point3f, b: Point3f) -> Self {
+        Self::new(
+            Interval::new(a.x.min(b.x), a.x.max(b.x)),
+            Interval::new(a.y.min(b.y), a.y.max(b.y)),
+            Interval::new(a.z.min(b.z), a.z.max(b.z)),
+        )
+    }
+
+    /// smallest box containing both a and b
+    pub fn surrounding(a: &Aabb, b: &Aabb) -> Self {
+        Self::new(
+            Interval::enclosing(&a.x, &b.x),
+            Interval::enclosing(&a.y, &b.y),
+            Interval::enclosing(&a.z, &b.z),
+        )
+    }
+
+    /// Grow axes thinner than delta so flat shapes still get a box with volume,
+    /// the slab test misses zero width boxes
+    pub fn padded(&self, delta: f64) -> Self {
+        let pad = |i: &Interval| {
+            if i.size() < delta {
+                i.expand(delta)
+            } else {
+                *i
+            }
+        };
+        Self::new(pad(&self.x), pad(&self.y), pad(&self.z))
+    }
+
+    /// interval for axis n, 0 is x, 1 is y and 2 is z
+    pub fn axis_interval(&self, n: usize) -> &Interval {
+        match n {
+            1 => &self.y,
+            2 => &self.z,
+            _ => &self.x,
+        }
+    }
+
+    /// index of the axis with the largest extent
+    pub fn longest_axis(&self) -> usize {
+        if self.x.size() > self.y.size() {
+            if self.x.size() > self.z.size() { 0 } else { 2 }
+        } else if self.y.size() > self.z.size() {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// false for boxes of unbounded shapes like infinite planes
+    pub fn is_finite(&self) -> bool {
+        [self.x, self.y, self.z]
+            .iter()
+            .all(|i| i.min.is_finite() && i.max.is_finite())
+    }
+
+    pub fn centroid(&self) -> Point3f {
+        Point3f::new(
+            (self.x.min + self.x.max) / 2.0,
+            (self.y.min + self.y.max) / 2.0,
+            (self.z.min + self.z.max) / 2.0,
+        )
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        let (dx, dy, dz) = (self.x.size(), self.y.size(), self.z.size());
+        if dx < 0.0 || dy < 0.0 || dz < 0.0 {
+            return 0.0;
+        }
+        2.0 * (dx * dy + dy * dz + dz * dx)
+    }
+
+    /// Slab test, does the ray pass through the box within the interval
+    pub fn hit(&self, ray: &Ray, interval: Interval) -> bool {
+        let mut t_min = interval.min;
+        let mut t_max = interval.max;
+
+        for axis in 0..3 {
+            let slab = self.axis_interval(axis);
+            let inv_d = 1.0 / ray.direction[axis];
+            let origin = ray.origin[axis];
+
+            let t0 = (slab.min - origin) * inv_d;
+            let t1 = (slab.max - origin) * inv_d;
+            let (t0, t1) = if inv_d < 0.0 { (t1, t0) } else { (t0, t1) };
+
+            // written so NaN from 0 * inf leaves the bounds untouched
+            if t0 > t_min {
+                t_min = t0;
+            }
+            if t1 < t_max {
+                t_max = t1;
+            }
+
+            if t_max <= t_min {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Vector3f;
+
+    #[test]
+    fn hit_through_center() {
+        let bbox = Aabb::from_points(
+            Vector3f::new(-1.0, -1.0, -1.0),
+            Vector3f::new(1.0, 1.0, 1.0),
+        );
+        let ray = Ray::new(Vector3f::new(0.0, 0.0, 5.0), Vector3f::new(0.0, 0.0, -1.0));
+        assert!(bbox.hit(&ray, Interval::new(0.0, f64::INFINITY)));
+        assert!(!bbox.hit(&ray, Interval::new(0.0, 3.0)));
+    }
+
+    #[test]
+    fn miss_beside() {
+        let bbox = Aabb::from_points(
+            Vector3f::new(-1.0, -1.0, -1.0),
+            Vector3f::new(1.0, 1.0, 1.0),
+        );
+        let ray = Ray::new(Vector3f::new(2.0, 0.0, 5.0), Vector3f::new(0.0, 0.0, -1.0));
+        assert!(!bbox.hit(&ray, Interval::new(0.0, f64::INFINITY)));
+    }
+
+    #[test]
+    fn surrounding() {
+        let a = Aabb::from_points(Vector3f::new(0.0, 0.0, 0.0), Vector3f::new(1.0, 1.0, 1.0));
+        let b = Aabb::from_points(Vector3f::new(2.0, -1.0, 0.0), Vector3f::new(3.0, 0.0, 4.0));
+        let c = Aabb::surrounding(&a, &b);
+        assert_eq!(c.centroid(), Vector3f::new(1.5, 0.0, 2.0));
+        assert_eq!(c.longest_axis(), 2);
+    }
+}
@@ -0,0 +1,331 @@
+use crate::{
+    geometry::{
+        aabb::{self, Aabb},
+        intersectable::{HitRecord, Intersectable},
+    },
+    interval::Interval,
+    math::{Point3f, Ray},
+};
+
+/// Leaves with this many items or fewer are not split further
+const MAX_LEAF_SIZE: usize = 4;
+/// Number of buckets the centroids are binned into when evaluating splits
+const SAH_BUCKETS: usize = 12;
+
+#[derive(Debug)]
+enum NodeKind {
+    /// items[first..first + count]
+    Leaf { first: usize, count: usize },
+    /// the first child is stored right after its parent
+    Interior { second_child: usize, axis: usize },
+}
+
+#[derive(Debug)]
+struct Node {
+    bbox: Aabb,
+    kind: NodeKind,
+}
+
+struct Primitive {
+    bbox: Aabb,
+    centroid: Point3f,
+    index: usize,
+}
+
+/// Bounding volume hierarchy over a set of items, built with the surface area
+/// heuristic and stored as a flat array of nodes in depth first order. Items
+/// with an unbounded box, like infinite planes, are kept out of the tree and
+/// tested one by one
+pub struct Bvh<T> {
+    nodes: Vec<Node>,
+    items: Vec<T>,
+    /// items[bounded..] have no finite box
+    bounded: usize,
+}
+
+impl<T: Intersectable> Bvh<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        let (mut primitives, unbounded): (Vec<Primitive>, Vec<Primitive>) = items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let bbox = item.bounding_box();
+                Primitive {
+                    bbox,
+                    centroid: bbox.centroid(),
+                    index,
+                }
+            })
+            .partition(|p| p.bbox.is_finite());
+
+        let mut nodes = Vec::new();
+        if !primitives.is_empty() {
+            build(&mut nodes, &mut primitives, 0);
+        }
+
+        // store the items in leaf order so each leaf is a contiguous range
+        let mut slots: Vec<Option<T>> = items.into_iter().map(Some).collect();
+        let items = primitives
+            .iter()
+            .chain(&unbounded)
+            .map(|p| slots[p.index].take().expect("item used twice"))
+            .collect();
+
+        Self {
+            nodes,
+            items,
+            bounded: primitives.len(),
+        }
+    }
+
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+}
+
+fn bounds<'a>(boxes: impl Iterator<Item = &'a Aabb>) -> Aabb {
+    boxes.fold(aabb::EMPTY, |acc, b| Aabb::surrounding(&acc, b))
+}
+
+/// Build the subtree for primitives, which start at offset in the final item
+/// order, and return the index of its root node
+fn build(nodes: &mut Vec<Node>, primitives: &mut [Primitive], offset: usize) -> usize {
+    let node_index = nodes.len();
+    nodes.push(Node {
+        bbox: bounds(primitives.iter().map(|p| &p.bbox)),
+        kind: NodeKind::Leaf {
+            first: offset,
+            count: primitives.len(),
+        },
+    });
+
+    if primitives.len() <= MAX_LEAF_SIZE {
+        return node_index;
+    }
+
+    let centroid_bounds = primitives.iter().fold(aabb::EMPTY, |acc, p| {
+        Aabb::surrounding(&acc, &Aabb::from_points(p.centroid, p.centroid))
+    });
+    let axis = centroid_bounds.longest_axis();
+    let mid = sah_split(primitives, &centroid_bounds, axis).unwrap_or_else(|| {
+        // all centroids in the same bucket, fall back to splitting at the median
+        let mid = primitives.len() / 2;
+        primitives
+            .select_nth_unstable_by(mid, |a, b| a.centroid[axis].total_cmp(&b.centroid[axis]));
+        mid
+    });
+
+    let (left, right) = primitives.split_at_mut(mid);
+    build(nodes, left, offset);
+    let second_child = build(nodes, right, offset + mid);
+    nodes[node_index].kind = NodeKind::Interior { second_child, axis };
+
+    node_index
+}
+
+/// Partition primitives at the bucket boundary with the lowest surface area
+/// heuristic cost, returns the number of primitives on the left side
+fn sah_split(primitives: &mut [Primitive], centroid_bounds: &Aabb, axis: usize) -> Option<usize> {
+    let extent = centroid_bounds.axis_interval(axis);
+    if extent.size() <= 0.0 {
+        return None;
+    }
+
+    let bucket_of = |p: &Primitive| {
+        let b = ((p.centroid[axis] - extent.min) / extent.size() * SAH_BUCKETS as f64) as usize;
+        b.min(SAH_BUCKETS - 1)
+    };
+
+    let mut counts = [0usize; SAH_BUCKETS];
+    let mut boxes = [aabb::EMPTY; SAH_BUCKETS];
+    for p in primitives.iter() {
+        let b = bucket_of(p);
+        counts[b] += 1;
+        boxes[b] = Aabb::surrounding(&boxes[b], &p.bbox);
+    }
+
+    // cost of splitting after bucket i is area(left) * n(left) + area(right) * n(right)
+    let mut best: Option<(usize, f64)> = None;
+    for split in 0..SAH_BUCKETS - 1 {
+        let n_left: usize = counts[..=split].iter().sum();
+        let n_right: usize = counts[split + 1..].iter().sum();
+        if n_left == 0 || n_right == 0 {
+            continue;
+        }
+
+        let cost = bounds(boxes[..=split].iter()).surface_area() * n_left as f64
+            + bounds(boxes[split + 1..].iter()).surface_area() * n_right as f64;
+        if best.is_none_or(|(_, best_cost)| cost < best_cost) {
+            best = Some((split, cost));
+        }
+    }
+
+    let (split, _) = best?;
+
+    // in place partition, buckets <= split to the front
+    let mut mid = 0;
+    for i in 0..primitives.len() {
+        if bucket_of(&primitives[i]) <= split {
+            primitives.swap(i, mid);
+            mid += 1;
+        }
+    }
+
+    Some(mid)
+}
+
+impl<T: Intersectable> Intersectable for Bvh<T> {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        let mut closest = None;
+        let mut closest_so_far = interval.max;
+
+        for item in &self.items[self.bounded..] {
+            if let Some(hit) = item.intersect(ray, Interval::new(interval.min, closest_so_far)) {
+                closest_so_far = hit.time;
+                closest = Some(hit);
+            }
+        }
+
+        let mut stack = if self.nodes.is_empty() {
+            Vec::new()
+        } else {
+            vec![0]
+        };
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node
+                .bbox
+                .hit(ray, Interval::new(interval.min, closest_so_far))
+            {
+                continue;
+            }
+
+            match node.kind {
+                NodeKind::Leaf { first, count } => {
+                    for item in &self.items[first..first + count] {
+                        if let Some(hit) =
+                            item.intersect(ray, Interval::new(interval.min, closest_so_far))
+                        {
+                            closest_so_far = hit.time;
+                            closest = Some(hit);
+                        }
+                    }
+                }
+                NodeKind::Interior { second_child, axis } => {
+                    // visit the child nearer along the ray first so the far one
+                    // can be culled by a closer hit
+                    if ray.direction[axis] < 0.0 {
+                        stack.push(index + 1);
+                        stack.push(second_child);
+                    } else {
+                        stack.push(second_child);
+                        stack.push(index + 1);
+                    }
+                }
+            }
+        }
+
+        closest
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        if self.bounded < self.items.len() {
+            return aabb::UNIVERSE;
+        }
+        self.nodes.first().map_or(aabb::EMPTY, |root| root.bbox)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        image::color,
+        materials::{lambertian::Lambertian, material::Materials},
+        math::{Vector3f, utils},
+        shapes::{plane::Plane, shapes::Shapes, sphere::Sphere},
+    };
+
+    fn random_spheres(n: usize) -> Vec<Sphere> {
+        utils::seed(7);
+        (0..n)
+            .map(|_| {
+                Sphere::new(
+                    utils::random_range(0.05, 0.5),
+                    Vector3f::new(
+                        utils::random_range(-10.0, 10.0),
+                        utils::random_range(-10.0, 10.0),
+                        utils::random_range(-10.0, 10.0),
+                    ),
+                    Materials::Lambertian(Lambertian::new(color::RED)),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_linear_search() {
+        let spheres = random_spheres(200);
+        let bvh = Bvh::new(random_spheres(200));
+        let interval = Interval::new(0.001, f64::INFINITY);
+
+        for _ in 0..200 {
+            let ray = Ray::new(
+                Vector3f::new(0.0, 0.0, 20.0),
+                Vector3f::new(
+                    utils::random_range(-0.5, 0.5),
+                    utils::random_range(-0.5, 0.5),
+                    -1.0,
+                ),
+            );
+
+            let expected = spheres
+                .iter()
+                .filter_map(|s| s.intersect(&ray, interval))
+                .map(|hit| hit.time)
+                .min_by(f64::total_cmp);
+            let actual = bvh.intersect(&ray, interval).map(|hit| hit.time);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn bounding_box_covers_items() {
+        let bvh = Bvh::new(random_spheres(50));
+        let bbox = bvh.bounding_box();
+        for sphere in bvh.items() {
+            let b = sphere.bounding_box();
+            assert!(bbox.x.min <= b.x.min && b.x.max <= bbox.x.max);
+            assert!(bbox.y.min <= b.y.min && b.y.max <= bbox.y.max);
+            assert!(bbox.z.min <= b.z.min && b.z.max <= bbox.z.max);
+        }
+    }
+
+    #[test]
+    fn unbounded_items() {
+        let mut shapes: Vec<Shapes> = random_spheres(20).into_iter().map(Shapes::Sphere).collect();
+        shapes.push(Shapes::Plane(Plane::new(
+            Vector3f::new(0.0, -20.0, 0.0),
+            Vector3f::new(0.0, 1.0, 0.0),
+            Materials::Lambertian(Lambertian::new(color::GREEN)),
+        )));
+        let bvh = Bvh::new(shapes);
+
+        assert!(!bvh.bounding_box().is_finite());
+        let down = Ray::new(Vector3f::new(30.0, 0.0, 0.0), Vector3f::new(0.0, -1.0, 0.0));
+        let hit = bvh.intersect(&down, Interval::new(0.001, f64::INFINITY));
+        assert!(hit.is_some_and(|hit| utils::is_close_to(&hit.time, &20.0)));
+    }
+
+    #[test]
+    fn empty() {
+        let bvh: Bvh<Sphere> = Bvh::new(Vec::new());
+        let ray = Ray::new(Vector3f::new(0.0, 0.0, 0.0), Vector3f::new(0.0, 0.0, -1.0));
+        assert!(
+            bvh.intersect(&ray, Interval::new(0.0, f64::INFINITY))
+                .is_none()
+        );
+    }
+}
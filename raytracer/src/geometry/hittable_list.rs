@@ -0,0 +1,118 @@
+use crate::{
+    geometry::{
+        aabb::{self, Aabb},
+        intersectable::{HitRecord, Intersectable},
+    },
+    interval::Interval,
+    math::Ray,
+    shapes::shapes::Shapes,
+};
+
+/// List of shapes tested one after the other, the closest hit wins
+#[derive(Default)]
+pub struct HittableList {
+    objects: Vec<Shapes>,
+    bbox: Option<Aabb>,
+}
+
+impl HittableList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, object: Shapes) {
+        let bbox = object.bounding_box();
+        self.bbox = Some(match &self.bbox {
+            Some(b) => Aabb::surrounding(b, &bbox),
+            None => bbox,
+        });
+        self.objects.push(object);
+    }
+
+    pub fn objects(&self) -> &[Shapes] {
+        &self.objects
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// take the shapes out, leaving the list empty
+    pub fn take(&mut self) -> Vec<Shapes> {
+        self.bbox = None;
+        std::mem::take(&mut self.objects)
+    }
+}
+
+impl From<Vec<Shapes>> for HittableList {
+    fn from(objects: Vec<Shapes>) -> Self {
+        let mut list = Self::new();
+        for object in objects {
+            list.add(object);
+        }
+        list
+    }
+}
+
+impl Intersectable for HittableList {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        let mut closest = None;
+        let mut closest_so_far = interval.max;
+
+        for obj in self.objects.iter() {
+            if let Some(hit) = obj.intersect(ray, Interval::new(interval.min, closest_so_far)) {
+                closest_so_far = hit.time;
+                closest = Some(hit);
+            }
+        }
+
+        closest
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox.unwrap_or(aabb::EMPTY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        image::color,
+        materials::{lambertian::Lambertian, material::Materials},
+        math::Vector3f,
+        shapes::sphere::Sphere,
+    };
+
+    fn sphere(z: f64) -> Shapes {
+        Shapes::Sphere(Sphere::new(
+            0.5,
+            Vector3f::new(0.0, 0.0, z),
+            Materials::Lambertian(Lambertian::new(color::RED)),
+        ))
+    }
+
+    #[test]
+    fn closest_hit_wins() {
+        let list = HittableList::from(vec![sphere(-5.0), sphere(-2.0), sphere(-8.0)]);
+        let ray = Ray::new(Vector3f::new(0.0, 0.0, 0.0), Vector3f::new(0.0, 0.0, -1.0));
+
+        let hit = list
+            .intersect(&ray, Interval::new(0.0, f64::INFINITY))
+            .unwrap();
+        assert_eq!(hit.point, Vector3f::new(0.0, 0.0, -1.5));
+    }
+
+    #[test]
+    fn nested_lists() {
+        let inner = HittableList::from(vec![sphere(-2.0)]);
+        let list = HittableList::from(vec![sphere(-5.0), Shapes::List(inner)]);
+        let ray = Ray::new(Vector3f::new(0.0, 0.0, 0.0), Vector3f::new(0.0, 0.0, -1.0));
+
+        let hit = list
+            .intersect(&ray, Interval::new(0.0, f64::INFINITY))
+            .unwrap();
+        assert_eq!(hit.point, Vector3f::new(0.0, 0.0, -1.5));
+        assert_eq!(list.bounding_box().z.min, -5.5);
+    }
+}
@@ -1,61 +1,66 @@
-use crate::image::color::Color;
+use crate::geometry::aabb::Aabb;
 use crate::interval::Interval;
+use crate::materials::material::Materials;
 use crate::math::{Normal3f, Point3f, Ray};
 
-pub struct HitRecord {
+pub struct HitRecord<'a> {
     /// point where intersection happend
     pub point: Point3f,
     /// normal at intersection point
     pub normal: Normal3f,
-    /// color at intersection point
-    pub color: Color<f64>,
-    /// time of intersection
+    /// material of the surface that was hit
+    pub material: &'a Materials,
+    /// ray parameter t of the intersection, not the shutter time
     pub time: f64,
     /// if the object front facing or back facing
     pub is_front_face: bool,
     pub ray: Ray,
+    /// surface coordinates at the intersection point
+    pub u: f64,
+    pub v: f64,
+    /// weights of the three vertices when a triangle was hit
+    pub barycentric: Option<[f64; 3]>,
 }
 
-impl HitRecord {
-    pub fn new(point: Point3f, normal: Normal3f, color: Color<f64>, time: f64, ray: Ray) -> Self {
+impl<'a> HitRecord<'a> {
+    pub fn new(
+        point: Point3f,
+        normal: Normal3f,
+        material: &'a Materials,
+        time: f64,
+        ray: Ray,
+    ) -> Self {
         let is_front_face = ray.direction.dot(&normal) < 0.0;
         let norm = if is_front_face { normal } else { -normal };
 
         Self {
-            point: point,
+            point,
             normal: norm,
-            color: color,
-            time: time,
-            is_front_face: is_front_face,
-            ray: ray,
+            material,
+            time,
+            is_front_face,
+            ray,
+            u: 0.0,
+            v: 0.0,
+            barycentric: None,
         }
     }
+
+    pub fn with_uv(mut self, u: f64, v: f64) -> Self {
+        self.u = u;
+        self.v = v;
+        self
+    }
+
+    pub fn with_barycentric(mut self, barycentric: [f64; 3]) -> Self {
+        self.barycentric = Some(barycentric);
+        self
+    }
 }
 
 pub trait Intersectable {
-    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord>;
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>>;
+
+    /// box enclosing the whole object, used to build acceleration structures
+    fn bounding_box(&self) -> Aabb;
 }
-//
-//fn intersect_obj(obj: &Geometry, ray: &Ray, interval: Interval) -> Option<HitRecord> {
-//    match obj {
-//        Geometry::Sphere(s) => s.intersect(ray, interval),
-//    }
-//}
-//
-//pub fn intersect(
-//    items: &Vec<Geometry>,
-//    ray: &Ray,
-//    interval: Interval,
-//) -> Option<super::intersectable::HitRecord> {
-//    let mut closest: Option<HitRecord> = None;
-//    let mut closest_time = interval.max;
-//
-//    for obj in items.iter() {
-//        if let Some(hit) = intersect_obj(obj, ray, Interval::new(interval.min, closest_time)) {
-//            closest_time = hit.time;
-//            closest = Some(hit);
-//        }
-//    }
-//
-//    closest
-//}
@@ -1 +1,4 @@
+pub mod aabb;
+pub mod bvh;
+pub mod hittable_list;
 pub mod intersectable;
@@ -2,7 +2,7 @@ use std::ops::{Add, Mul};
 
 use crate::{interval::UNIT, math::Vector3f};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Color<T> {
     pub r: T,
     pub g: T,
@@ -22,12 +22,12 @@ impl<T> Color<T> {
 
     pub fn as_u8(&self) -> Color<u8>
     where
-        T: Into<f64> + Clone,
+        T: Into<f64> + Copy,
     {
         Color::new(
-            (256.0 * UNIT.clamp(self.r.clone().into())) as u8,
-            (256.0 * UNIT.clamp(self.g.clone().into())) as u8,
-            (256.0 * UNIT.clamp(self.b.clone().into())) as u8,
+            (256.0 * UNIT.clamp(self.r.into())) as u8,
+            (256.0 * UNIT.clamp(self.g.into())) as u8,
+            (256.0 * UNIT.clamp(self.b.into())) as u8,
         )
     }
 }
@@ -56,6 +56,14 @@ impl<T: Mul<f64, Output = T>> Mul<f64> for Color<T> {
     }
 }
 
+impl<T: Mul<T, Output = T>> Mul<Color<T>> for Color<T> {
+    type Output = Color<T>;
+
+    fn mul(self, rhs: Color<T>) -> Self::Output {
+        Self::Output::new(self.r * rhs.r, self.g * rhs.g, self.b * rhs.b)
+    }
+}
+
 impl<T: Add<f64, Output = f64>> Add<Vector3f> for Color<T> {
     type Output = Color<f64>;
 
@@ -78,3 +86,4 @@ pub const RED: ColorNormalized = Color::new(1.0, 0.0, 0.0);
 pub const BLUE: ColorNormalized = Color::new(0.0, 0.0, 1.0);
 pub const BLACK: ColorNormalized = Color::new(0.0, 0.0, 0.0);
 pub const GREEN: ColorNormalized = Color::new(0.0, 1.0, 0.0);
+pub const WHITE: ColorNormalized = Color::new(1.0, 1.0, 1.0);
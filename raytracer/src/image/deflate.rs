@@ -0,0 +1,482 @@
+use crate::image::checksum;
+
+/// Largest payload of a stored block
+const MAX_STORED_BLOCK: usize = 65535;
+/// LZ77 window, the farthest back a match can reference
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+/// How many earlier positions with the same hash are tried per match
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Writes bits least significant first, the order deflate packs them
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn write_bits(&mut self, value: u32, bits: u32) {
+        self.buffer |= u64::from(value) << self.count;
+        self.count += bits;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Huffman codes are defined most significant bit first
+    fn write_code(&mut self, code: u32, bits: u32) {
+        self.write_bits(code.reverse_bits() >> (32 - bits), bits);
+    }
+
+    fn align_to_byte(&mut self) {
+        if self.count > 0 {
+            self.write_bits(0, 8 - self.count);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.align_to_byte();
+        self.bytes
+    }
+}
+
+enum Symbol {
+    Literal(u8),
+    Match { length: usize, distance: usize },
+}
+
+/// Hash chains over the last WINDOW_SIZE bytes to find repeated sequences
+struct Matcher<'a> {
+    data: &'a [u8],
+    /// most recent position for each hash
+    head: Vec<usize>,
+    /// previous position with the same hash as position i
+    prev: Vec<usize>,
+}
+
+impl<'a> Matcher<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            head: vec![usize::MAX; 1 << HASH_BITS],
+            prev: vec![usize::MAX; data.len()],
+        }
+    }
+
+    fn hash(&self, i: usize) -> usize {
+        let v = u32::from(self.data[i]) << 16
+            | u32::from(self.data[i + 1]) << 8
+            | u32::from(self.data[i + 2]);
+        (v.wrapping_mul(0x9E37_79B1) >> (32 - HASH_BITS)) as usize
+    }
+
+    fn insert(&mut self, i: usize) {
+        if i + MIN_MATCH <= self.data.len() {
+            let h = self.hash(i);
+            self.prev[i] = self.head[h];
+            self.head[h] = i;
+        }
+    }
+
+    /// longest earlier match for the bytes at i as (length, distance)
+    fn find(&self, i: usize) -> (usize, usize) {
+        if i + MIN_MATCH > self.data.len() {
+            return (0, 0);
+        }
+
+        let max_length = MAX_MATCH.min(self.data.len() - i);
+        let mut best = (0, 0);
+        let mut candidate = self.head[self.hash(i)];
+        let mut chain = 0;
+
+        while candidate != usize::MAX && i - candidate <= WINDOW_SIZE && chain < MAX_CHAIN {
+            let length = self.data[candidate..]
+                .iter()
+                .zip(&self.data[i..i + max_length])
+                .take_while(|(a, b)| a == b)
+                .count();
+            if length > best.0 {
+                best = (length, i - candidate);
+                if length == max_length {
+                    break;
+                }
+            }
+            candidate = self.prev[candidate];
+            chain += 1;
+        }
+
+        best
+    }
+}
+
+fn lz77(data: &[u8]) -> Vec<Symbol> {
+    let mut matcher = Matcher::new(data);
+    let mut symbols = Vec::new();
+    let mut i = 0;
+
+    while i < data.len() {
+        let (length, distance) = matcher.find(i);
+
+        if length >= MIN_MATCH {
+            symbols.push(Symbol::Match { length, distance });
+            for j in i..i + length {
+                matcher.insert(j);
+            }
+            i += length;
+        } else {
+            symbols.push(Symbol::Literal(data[i]));
+            matcher.insert(i);
+            i += 1;
+        }
+    }
+
+    symbols
+}
+
+/// Write a literal/length symbol with the fixed Huffman code from RFC 1951 3.2.6
+fn write_fixed_literal(writer: &mut BitWriter, symbol: u32) {
+    match symbol {
+        0..=143 => writer.write_code(0x30 + symbol, 8),
+        144..=255 => writer.write_code(0x190 + symbol - 144, 9),
+        256..=279 => writer.write_code(symbol - 256, 7),
+        _ => writer.write_code(0xC0 + symbol - 280, 8),
+    }
+}
+
+/// index of the last entry in base that is <= value
+fn code_index(base: &[u16], value: usize) -> usize {
+    base.iter()
+        .rposition(|&b| usize::from(b) <= value)
+        .unwrap_or(0)
+}
+
+/// Single final block compressed with the fixed Huffman codes
+fn fixed_block(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    // BFINAL = 1, BTYPE = 01
+    writer.write_bits(1, 1);
+    writer.write_bits(1, 2);
+
+    for symbol in lz77(data) {
+        match symbol {
+            Symbol::Literal(byte) => write_fixed_literal(&mut writer, u32::from(byte)),
+            Symbol::Match { length, distance } => {
+                let l = code_index(&LENGTH_BASE, length);
+                write_fixed_literal(&mut writer, 257 + l as u32);
+                writer.write_bits(
+                    (length - usize::from(LENGTH_BASE[l])) as u32,
+                    u32::from(LENGTH_EXTRA[l]),
+                );
+
+                let d = code_index(&DISTANCE_BASE, distance);
+                writer.write_code(d as u32, 5);
+                writer.write_bits(
+                    (distance - usize::from(DISTANCE_BASE[d])) as u32,
+                    u32::from(DISTANCE_EXTRA[d]),
+                );
+            }
+        }
+    }
+
+    // end of block
+    write_fixed_literal(&mut writer, 256);
+    writer.finish()
+}
+
+/// Uncompressed blocks, used when compression does not pay off
+fn stored_blocks(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + 5 * (data.len() / MAX_STORED_BLOCK + 1));
+    let mut chunks = data.chunks(MAX_STORED_BLOCK).peekable();
+
+    if chunks.peek().is_none() {
+        // an empty stream still needs one final block
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+
+    while let Some(chunk) = chunks.next() {
+        // BFINAL on the last block, BTYPE = 00, padded to the byte boundary
+        out.push(u8::from(chunks.peek().is_none()));
+        let len = chunk.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(chunk);
+    }
+
+    out
+}
+
+/// Raw deflate stream (RFC 1951), whichever of fixed Huffman or stored blocks is smaller
+pub fn deflate(data: &[u8]) -> Vec<u8> {
+    let compressed = fixed_block(data);
+    if compressed.len() < data.len() + 5 {
+        compressed
+    } else {
+        stored_blocks(data)
+    }
+}
+
+/// Deflate stream wrapped in a zlib container (RFC 1950)
+pub fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    // CMF: deflate with a 32K window, FLG: default level, header % 31 == 0
+    let mut out = vec![0x78, 0x9C];
+    out.extend(deflate(data));
+    out.extend_from_slice(&checksum::adler32(data).to_be_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads bits least significant first, mirroring BitWriter
+    struct BitReader<'a> {
+        bytes: &'a [u8],
+        position: usize,
+        bit: u32,
+    }
+
+    impl BitReader<'_> {
+        fn bits(&mut self, count: u32) -> u32 {
+            let mut value = 0;
+            for i in 0..count {
+                let byte = self.bytes[self.position];
+                value |= u32::from((byte >> self.bit) & 1) << i;
+                self.bit += 1;
+                if self.bit == 8 {
+                    self.bit = 0;
+                    self.position += 1;
+                }
+            }
+            value
+        }
+
+        /// Huffman code bits come most significant first
+        fn code_bit(&mut self, code: u32) -> u32 {
+            code << 1 | self.bits(1)
+        }
+
+        fn align_to_byte(&mut self) {
+            if self.bit > 0 {
+                self.bit = 0;
+                self.position += 1;
+            }
+        }
+
+        /// literal/length symbol with the fixed Huffman code
+        fn fixed_literal(&mut self) -> u32 {
+            let mut code = 0;
+            for _ in 0..7 {
+                code = self.code_bit(code);
+            }
+            if code <= 0x17 {
+                return 256 + code;
+            }
+            code = self.code_bit(code);
+            match code {
+                0x30..=0xBF => code - 0x30,
+                0xC0..=0xC7 => 280 + code - 0xC0,
+                _ => 144 + self.code_bit(code) - 0x190,
+            }
+        }
+    }
+
+    /// (base, extra bits) of length code l, built from the RFC 1951 3.2.5
+    /// rules rather than the encoder's tables so a wrong entry shows up
+    fn length_code(l: usize) -> (usize, u32) {
+        let extra = |i: usize| if i < 8 { 0 } else { (i as u32 - 4) / 4 };
+        if l == 28 {
+            return (258, 0);
+        }
+        let base = 3 + (0..l).map(|i| 1 << extra(i)).sum::<usize>();
+        (base, extra(l))
+    }
+
+    /// (base, extra bits) of distance code d, from the RFC 1951 3.2.5 rules
+    fn distance_code(d: usize) -> (usize, u32) {
+        let extra = |i: usize| if i < 4 { 0 } else { (i as u32 - 2) / 2 };
+        let base = 1 + (0..d).map(|i| 1 << extra(i)).sum::<usize>();
+        (base, extra(d))
+    }
+
+    /// Minimal inflate for stored and fixed Huffman blocks, enough to decode
+    /// everything the encoder writes
+    fn inflate(stream: &[u8]) -> Vec<u8> {
+        let mut reader = BitReader {
+            bytes: stream,
+            position: 0,
+            bit: 0,
+        };
+        let mut out: Vec<u8> = Vec::new();
+
+        loop {
+            let last = reader.bits(1) == 1;
+            match reader.bits(2) {
+                0 => {
+                    reader.align_to_byte();
+                    let len = reader.bits(16);
+                    let nlen = reader.bits(16);
+                    assert_eq!(len, !nlen & 0xFFFF, "stored block length check");
+                    let start = reader.position;
+                    out.extend_from_slice(&stream[start..start + len as usize]);
+                    reader.position += len as usize;
+                }
+                1 => loop {
+                    let symbol = reader.fixed_literal();
+                    match symbol {
+                        0..=255 => out.push(symbol as u8),
+                        256 => break,
+                        _ => {
+                            let (base, extra) = length_code((symbol - 257) as usize);
+                            let length = base + reader.bits(extra) as usize;
+                            let mut d = 0;
+                            for _ in 0..5 {
+                                d = reader.code_bit(d);
+                            }
+                            let (base, extra) = distance_code(d as usize);
+                            let distance = base + reader.bits(extra) as usize;
+                            assert!(distance <= out.len(), "distance before the start");
+                            // copies may overlap the bytes they produce
+                            for _ in 0..length {
+                                out.push(out[out.len() - distance]);
+                            }
+                        }
+                    }
+                },
+                btype => panic!("unexpected block type {btype}"),
+            }
+            if last {
+                return out;
+            }
+        }
+    }
+
+    /// deterministic bytes that do not compress
+    fn noise(len: usize, seed: u32) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (state >> 24) as u8
+            })
+            .collect()
+    }
+
+    fn round_trip(data: &[u8]) {
+        assert_eq!(inflate(&fixed_block(data)), data, "fixed Huffman");
+        assert_eq!(inflate(&stored_blocks(data)), data, "stored");
+        assert_eq!(inflate(&deflate(data)), data, "deflate");
+    }
+
+    #[test]
+    fn round_trip_small() {
+        round_trip(b"");
+        round_trip(b"a");
+        round_trip(b"hello hello hello hello");
+        round_trip(&[0xFF; 1000]);
+        round_trip(&(0..=255).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn round_trip_long_back_references() {
+        // a block repeated at the far end of the window, and every match
+        // length from 3 to 258 at growing distances
+        let block = noise(2000, 1);
+        let mut data = block.clone();
+        data.extend(noise(WINDOW_SIZE - 2 * block.len(), 2));
+        data.extend(&block);
+        for length in MIN_MATCH..=MAX_MATCH {
+            let start = data.len() - length * 37;
+            data.extend_from_within(start..start + length);
+            data.extend(noise(3, length as u32));
+        }
+        round_trip(&data);
+
+        let distances = lz77(&data).into_iter().filter_map(|s| match s {
+            Symbol::Match { distance, .. } => Some(distance),
+            Symbol::Literal(_) => None,
+        });
+        assert!(distances.max().unwrap() > WINDOW_SIZE - 2 * block.len());
+    }
+
+    #[test]
+    fn round_trip_larger_than_a_stored_block() {
+        let data = noise(MAX_STORED_BLOCK * 2 + 1234, 3);
+        round_trip(&data);
+        // incompressible data takes the stored path
+        assert_eq!(deflate(&data)[0] & 0b110, 0);
+
+        let data: Vec<u8> = (0..200_000u64).map(|i| (i * i / 7 % 251) as u8).collect();
+        round_trip(&data);
+        assert_eq!(deflate(&data)[0] & 0b110, 0b010);
+    }
+
+    #[test]
+    fn zlib_header_and_trailer() {
+        let data = b"hello hello hello hello";
+        let out = zlib_compress(data);
+        assert_eq!((u16::from(out[0]) << 8 | u16::from(out[1])) % 31, 0);
+        assert_eq!(out[out.len() - 4..], checksum::adler32(data).to_be_bytes());
+    }
+
+    #[test]
+    fn stored_block_layout() {
+        let out = stored_blocks(b"abc");
+        assert_eq!(out, vec![1, 3, 0, 0xFC, 0xFF, b'a', b'b', b'c']);
+    }
+
+    #[test]
+    fn stored_blocks_split() {
+        let data = vec![7u8; MAX_STORED_BLOCK + 10];
+        let out = stored_blocks(&data);
+        assert_eq!(out[0], 0);
+        assert_eq!(out[5 + MAX_STORED_BLOCK], 1);
+        assert_eq!(out.len(), data.len() + 10);
+    }
+
+    #[test]
+    fn repetitive_data_compresses() {
+        let data: Vec<u8> = (0..10_000).map(|i| (i % 7) as u8).collect();
+        assert!(deflate(&data).len() < 100);
+    }
+
+    #[test]
+    fn fixed_codes() {
+        // 'a' is literal 97, code 0x30 + 97 in 8 bits, then end of block (7 zero bits)
+        let out = fixed_block(b"a");
+        let mut expected = BitWriter::new();
+        expected.write_bits(1, 1);
+        expected.write_bits(1, 2);
+        expected.write_code(0x30 + 97, 8);
+        expected.write_code(0, 7);
+        assert_eq!(out, expected.finish());
+    }
+}
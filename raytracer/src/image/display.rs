@@ -0,0 +1,159 @@
+use crate::{
+    image::{Color, Framebuffer},
+    interval::UNIT,
+};
+
+/// sRGB transfer function, linear light to display encoded value
+pub fn linear_to_srgb(x: f64) -> f64 {
+    if x <= 0.0031308 {
+        12.92 * x
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// inverse of the sRGB transfer function, display encoded value to linear light
+pub fn srgb_to_linear(x: f64) -> f64 {
+    if x <= 0.04045 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Operator compressing unbounded radiance into [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToneMap {
+    /// values above 1 are clipped
+    Clamp,
+    /// x / (1 + x), never reaches white
+    Reinhard,
+    /// Reinhard scaled so that `white` maps to 1, a white that is not positive
+    /// acts as the smallest positive one
+    ExtendedReinhard { white: f64 },
+    /// Narkowicz's fit of the ACES filmic curve
+    Aces,
+}
+
+impl ToneMap {
+    pub fn apply(&self, x: f64) -> f64 {
+        let x = x.max(0.0);
+        let mapped = match self {
+            ToneMap::Clamp => x,
+            ToneMap::Reinhard => x / (1.0 + x),
+            ToneMap::ExtendedReinhard { white } => {
+                let white = white.max(f64::EPSILON);
+                x * (1.0 + x / (white * white)) / (1.0 + x)
+            }
+            ToneMap::Aces => (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14),
+        };
+        UNIT.clamp(mapped)
+    }
+}
+
+/// Turns linear rendered radiance into display ready values: exposure, then
+/// tone mapping, then the sRGB transfer function
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayTransform {
+    /// exposure adjustment in stops, each stop doubles the brightness
+    pub exposure: f64,
+    pub tone_map: ToneMap,
+    /// encode with the sRGB transfer function, off leaves values linear
+    pub srgb: bool,
+}
+
+impl Default for DisplayTransform {
+    fn default() -> Self {
+        Self {
+            exposure: 0.0,
+            tone_map: ToneMap::Clamp,
+            srgb: true,
+        }
+    }
+}
+
+impl DisplayTransform {
+    /// only clamps to [0, 1], for images that are already display encoded
+    pub const IDENTITY: DisplayTransform = DisplayTransform {
+        exposure: 0.0,
+        tone_map: ToneMap::Clamp,
+        srgb: false,
+    };
+
+    pub fn apply(&self, color: Color<f64>) -> Color<f64> {
+        let scale = self.exposure.exp2();
+        let transform = |x: f64| {
+            let mapped = self.tone_map.apply(x * scale);
+            if self.srgb {
+                linear_to_srgb(mapped)
+            } else {
+                mapped
+            }
+        };
+
+        Color::new(transform(color.r), transform(color.g), transform(color.b))
+    }
+
+    pub fn apply_image(&self, image: &Framebuffer) -> Framebuffer {
+        Framebuffer::from_pixels(
+            image.width(),
+            image.height(),
+            image.pixels().iter().map(|&c| self.apply(c)).collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::utils::is_close_to;
+
+    #[test]
+    fn srgb_round_trip() {
+        for x in [0.0, 0.001, 0.2, 0.5, 1.0] {
+            assert!(is_close_to(&srgb_to_linear(linear_to_srgb(x)), &x));
+        }
+        assert!((linear_to_srgb(0.5) - 0.7354).abs() < 1e-4);
+        assert!(is_close_to(&linear_to_srgb(1.0), &1.0));
+    }
+
+    #[test]
+    fn reinhard() {
+        assert_eq!(ToneMap::Reinhard.apply(1.0), 0.5);
+        assert!(ToneMap::Reinhard.apply(1000.0) < 1.0);
+        assert!(is_close_to(
+            &ToneMap::ExtendedReinhard { white: 4.0 }.apply(4.0),
+            &1.0
+        ));
+    }
+
+    #[test]
+    fn non_positive_white_is_not_nan() {
+        for white in [0.0, -2.0, f64::NAN] {
+            let tone_map = ToneMap::ExtendedReinhard { white };
+            assert_eq!(tone_map.apply(0.0), 0.0);
+            assert_eq!(tone_map.apply(0.5), 1.0);
+        }
+    }
+
+    #[test]
+    fn aces_is_monotonic_and_bounded() {
+        let mut last = ToneMap::Aces.apply(0.0);
+        assert_eq!(last, 0.0);
+        for i in 1..100 {
+            let y = ToneMap::Aces.apply(f64::from(i) * 0.25);
+            assert!(y >= last && y <= 1.0);
+            last = y;
+        }
+    }
+
+    #[test]
+    fn exposure_doubles_per_stop() {
+        let display = DisplayTransform {
+            exposure: 1.0,
+            ..DisplayTransform::IDENTITY
+        };
+        let c = display.apply(Color::new(0.25, 0.1, 2.0));
+        assert_eq!(c, Color::new(0.5, 0.2, 1.0));
+    }
+}
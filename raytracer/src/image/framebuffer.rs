@@ -0,0 +1,154 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use crate::image::{Color, DisplayTransform, ImageFormat, color};
+
+/// Image held in memory as linear colors, rows from the top left
+#[derive(Debug, Clone, PartialEq)]
+pub struct Framebuffer {
+    width: u16,
+    height: u16,
+    pixels: Vec<Color<f64>>,
+}
+
+impl Framebuffer {
+    /// black image
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color::BLACK; usize::from(width) * usize::from(height)],
+        }
+    }
+
+    /// pixels must hold width * height colors in row order
+    pub fn from_pixels(width: u16, height: u16, pixels: Vec<Color<f64>>) -> Self {
+        assert_eq!(
+            pixels.len(),
+            usize::from(width) * usize::from(height),
+            "pixel count does not match a {width}x{height} image"
+        );
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Color<f64>] {
+        &self.pixels
+    }
+
+    fn index(&self, x: u16, y: u16) -> usize {
+        assert!(
+            x < self.width && y < self.height,
+            "pixel ({x}, {y}) out of bounds"
+        );
+        usize::from(y) * usize::from(self.width) + usize::from(x)
+    }
+
+    pub fn get(&self, x: u16, y: u16) -> Color<f64> {
+        self.pixels[self.index(x, y)]
+    }
+
+    pub fn set(&mut self, x: u16, y: u16, color: Color<f64>) {
+        let i = self.index(x, y);
+        self.pixels[i] = color;
+    }
+
+    /// overwrite row y with the given colors
+    pub fn set_row(&mut self, y: u16, row: &[Color<f64>]) {
+        let start = self.index(0, y);
+        self.pixels[start..start + usize::from(self.width)].copy_from_slice(row);
+    }
+
+    /// quantized copy of the pixels for 8 bit formats
+    pub fn to_u8(&self) -> Vec<Color<u8>> {
+        self.pixels.iter().map(|c| c.as_u8()).collect()
+    }
+
+    /// Write the image to path, the format is picked from the file extension.
+    /// The display transform maps the linear pixels to the 8 bit output, high
+    /// dynamic range formats store the pixels as they are.
+    pub fn save(&self, path: impl AsRef<Path>, display: &DisplayTransform) -> io::Result<()> {
+        let path = path.as_ref();
+        let format = format_of(path)?;
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        if format.is_high_dynamic_range() {
+            format.write(self, &mut writer)?;
+        } else {
+            format.write(&display.apply_image(self), &mut writer)?;
+        }
+        writer.flush()
+    }
+
+    /// Read the image at path, the format is picked from the file extension
+    /// (.ppm, .pgm, .hdr or .pfm)
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Framebuffer> {
+        let path = path.as_ref();
+        // grayscale netpbm goes through the same reader
+        let is_pgm = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("pgm"));
+        let format = if is_pgm {
+            ImageFormat::Ppm
+        } else {
+            format_of(path)?
+        };
+
+        let file = File::open(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+        format
+            .read(&mut BufReader::new(file))
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))
+    }
+}
+
+fn format_of(path: &Path) -> io::Result<ImageFormat> {
+    ImageFormat::from_path(path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{}: unsupported image format", path.display()),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_and_set() {
+        let mut image = Framebuffer::new(3, 2);
+        image.set(2, 1, color::RED);
+        assert_eq!(image.get(2, 1).r, 1.0);
+        assert_eq!(image.pixels()[5].r, 1.0);
+        assert_eq!(image.get(0, 0).r, 0.0);
+    }
+
+    #[test]
+    fn set_row() {
+        let mut image = Framebuffer::new(2, 2);
+        image.set_row(1, &[color::GREEN, color::BLUE]);
+        assert_eq!(image.get(0, 1).g, 1.0);
+        assert_eq!(image.get(1, 1).b, 1.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_bounds() {
+        Framebuffer::new(2, 2).get(2, 0);
+    }
+}
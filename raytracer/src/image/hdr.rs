@@ -0,0 +1,297 @@
+use std::io::{self, Read, Write};
+
+use crate::image::{Color, Framebuffer};
+
+/// Scanlines of this width use the adaptive run length encoding
+const MIN_RLE_WIDTH: usize = 8;
+const MAX_RLE_WIDTH: usize = 0x7FFF;
+/// Longest run a single count byte can encode
+const MAX_RUN: usize = 127;
+/// Longest literal sequence a single count byte can encode
+const MAX_LITERAL: usize = 128;
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Shared exponent encoding, the mantissas keep 8 bits relative to the largest channel
+fn to_rgbe(color: &Color<f64>) -> [u8; 4] {
+    let v = color.r.max(color.g).max(color.b);
+    if v.is_nan() || v < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+
+    // v = m * 2^e with m in [0.5, 1)
+    let mut e = v.log2().floor() as i32 + 1;
+    if v / f64::from(e).exp2() >= 1.0 {
+        e += 1;
+    }
+    let e = e.clamp(-128, 127);
+    let scale = 256.0 / f64::from(e).exp2();
+    let channel = |c: f64| (c.max(0.0) * scale).min(255.0) as u8;
+
+    [
+        channel(color.r),
+        channel(color.g),
+        channel(color.b),
+        (e + 128) as u8,
+    ]
+}
+
+fn from_rgbe(rgbe: [u8; 4]) -> Color<f64> {
+    if rgbe[3] == 0 {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+    // the channel is the middle of its quantization step
+    let f = (f64::from(rgbe[3]) - 136.0).exp2();
+    Color::new(
+        (f64::from(rgbe[0]) + 0.5) * f,
+        (f64::from(rgbe[1]) + 0.5) * f,
+        (f64::from(rgbe[2]) + 0.5) * f,
+    )
+}
+
+/// Run length encode one channel of a scanline
+fn write_rle_channel<W: Write>(data: &[u8], writer: &mut W) -> io::Result<()> {
+    let mut i = 0;
+    while i < data.len() {
+        let run = data[i..]
+            .iter()
+            .take(MAX_RUN)
+            .take_while(|&&b| b == data[i])
+            .count();
+
+        if run >= 3 {
+            writer.write_all(&[(128 + run) as u8, data[i]])?;
+            i += run;
+            continue;
+        }
+
+        // literal bytes until the next run of at least 3
+        let start = i;
+        while i < data.len() && i - start < MAX_LITERAL {
+            if i + 2 < data.len() && data[i] == data[i + 1] && data[i] == data[i + 2] {
+                break;
+            }
+            i += 1;
+        }
+        writer.write_all(&[(i - start) as u8])?;
+        writer.write_all(&data[start..i])?;
+    }
+    Ok(())
+}
+
+/// Write the image as Radiance RGBE (.hdr) with run length encoded scanlines
+pub fn write<W: Write>(image: &Framebuffer, writer: &mut W) -> io::Result<()> {
+    let width = usize::from(image.width());
+    write!(
+        writer,
+        "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+        image.height(),
+        image.width()
+    )?;
+
+    for row in image.pixels().chunks(width.max(1)) {
+        let rgbe: Vec<[u8; 4]> = row.iter().map(to_rgbe).collect();
+
+        if !(MIN_RLE_WIDTH..=MAX_RLE_WIDTH).contains(&width) {
+            writer.write_all(&rgbe.concat())?;
+            continue;
+        }
+
+        writer.write_all(&[2, 2, (width >> 8) as u8, (width & 0xFF) as u8])?;
+        for channel in 0..4 {
+            let data: Vec<u8> = rgbe.iter().map(|p| p[channel]).collect();
+            write_rle_channel(&data, writer)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Read a Radiance RGBE (.hdr) image, flat or run length encoded, in the
+/// standard `-Y height +X width` orientation
+pub fn read<R: Read>(reader: &mut R) -> io::Result<Framebuffer> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let mut position = 0;
+    let mut next_line = || -> io::Result<String> {
+        let rest = &data[position..];
+        let end = rest
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(|| invalid_data("header is truncated"))?;
+        position += end + 1;
+        Ok(String::from_utf8_lossy(&rest[..end]).into_owned())
+    };
+
+    if !next_line()?.starts_with("#?") {
+        return Err(invalid_data("missing #? Radiance signature"));
+    }
+
+    // header variables run until an empty line
+    loop {
+        let line = next_line()?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some(format) = line.strip_prefix("FORMAT=")
+            && format != "32-bit_rle_rgbe"
+        {
+            return Err(invalid_data(format!("unsupported format {format}")));
+        }
+    }
+
+    let resolution = next_line()?;
+    let fields: Vec<&str> = resolution.split_whitespace().collect();
+    let (height, width) = match fields[..] {
+        ["-Y", h, "+X", w] => (h.parse::<u16>().ok(), w.parse::<u16>().ok()),
+        _ => (None, None),
+    };
+    let (Some(height), Some(width)) = (height, width) else {
+        return Err(invalid_data(format!(
+            "unsupported resolution line '{resolution}'"
+        )));
+    };
+
+    let w = usize::from(width);
+    let mut bytes = data[position..].iter().copied();
+    let mut next = || {
+        bytes
+            .next()
+            .ok_or_else(|| invalid_data("pixel data is truncated"))
+    };
+    let mut pixels = Vec::with_capacity(w * usize::from(height));
+    // scanlines of an empty image hold no pixel data at all
+    let rows = if w == 0 { 0 } else { height };
+
+    for _ in 0..rows {
+        let first = [next()?, next()?, next()?, next()?];
+        let is_rle = (MIN_RLE_WIDTH..=MAX_RLE_WIDTH).contains(&w)
+            && first[0] == 2
+            && first[1] == 2
+            && first[2] & 0x80 == 0;
+
+        if !is_rle {
+            pixels.push(from_rgbe(first));
+            for _ in 1..w {
+                pixels.push(from_rgbe([next()?, next()?, next()?, next()?]));
+            }
+            continue;
+        }
+
+        if (usize::from(first[2]) << 8 | usize::from(first[3])) != w {
+            return Err(invalid_data("scanline width does not match the image"));
+        }
+
+        let mut scanline = vec![[0u8; 4]; w];
+        for channel in 0..4 {
+            let mut x = 0;
+            while x < w {
+                let count = usize::from(next()?);
+                if count > 128 {
+                    let run = count - 128;
+                    let value = next()?;
+                    if x + run > w {
+                        return Err(invalid_data("run overflows the scanline"));
+                    }
+                    for pixel in &mut scanline[x..x + run] {
+                        pixel[channel] = value;
+                    }
+                    x += run;
+                } else {
+                    if count == 0 || x + count > w {
+                        return Err(invalid_data("bad literal count in scanline"));
+                    }
+                    for pixel in &mut scanline[x..x + count] {
+                        pixel[channel] = next()?;
+                    }
+                    x += count;
+                }
+            }
+        }
+        pixels.extend(scanline.into_iter().map(from_rgbe));
+    }
+
+    Ok(Framebuffer::from_pixels(width, height, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: Color<f64>, b: Color<f64>) {
+        // 8 bit mantissa relative to the brightest channel
+        let tolerance = a.r.max(a.g).max(a.b) / 128.0;
+        assert!((a.r - b.r).abs() <= tolerance, "{a:?} != {b:?}");
+        assert!((a.g - b.g).abs() <= tolerance, "{a:?} != {b:?}");
+        assert!((a.b - b.b).abs() <= tolerance, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn rgbe_round_trip() {
+        for color in [
+            Color::new(1.0, 0.5, 0.25),
+            Color::new(1000.0, 3.0, 0.0),
+            Color::new(0.001, 0.002, 0.003),
+        ] {
+            assert_close(color, from_rgbe(to_rgbe(&color)));
+        }
+        assert_eq!(to_rgbe(&Color::new(0.0, 0.0, 0.0)), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn rgbe_exponent() {
+        // 1.0 = 0.5 * 2^1, mantissa 128
+        assert_eq!(to_rgbe(&Color::new(1.0, 1.0, 1.0)), [128, 128, 128, 129]);
+    }
+
+    #[test]
+    fn empty_image_round_trip() {
+        for (width, height) in [(0, 3), (3, 0)] {
+            let image = Framebuffer::new(width, height);
+            let mut out = Vec::new();
+            write(&image, &mut out).unwrap();
+            assert_eq!(read(&mut out.as_slice()).unwrap(), image);
+        }
+    }
+
+    #[test]
+    fn rle_image_round_trip() {
+        let pixels: Vec<Color<f64>> = (0..40 * 3)
+            .map(|i| {
+                if i % 40 < 20 {
+                    Color::new(5.0, 1.0, 0.5)
+                } else {
+                    Color::new(f64::from(i) * 0.1, 0.2, 0.0)
+                }
+            })
+            .collect();
+        let image = Framebuffer::from_pixels(40, 3, pixels);
+
+        let mut out = Vec::new();
+        write(&image, &mut out).unwrap();
+        let decoded = read(&mut out.as_slice()).unwrap();
+
+        assert_eq!((decoded.width(), decoded.height()), (40, 3));
+        for (a, b) in image.pixels().iter().zip(decoded.pixels()) {
+            assert_close(*a, *b);
+        }
+    }
+
+    #[test]
+    fn flat_narrow_image() {
+        let image = Framebuffer::from_pixels(2, 1, vec![Color::new(2.0, 2.0, 2.0); 2]);
+        let mut out = Vec::new();
+        write(&image, &mut out).unwrap();
+        let decoded = read(&mut out.as_slice()).unwrap();
+        assert_close(image.get(1, 0), decoded.get(1, 0));
+    }
+
+    #[test]
+    fn rejects_other_orientation() {
+        let data = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n+Y 1 +X 1\n\0\0\0\0";
+        assert!(read(&mut data.as_slice()).is_err());
+    }
+}
@@ -1,4 +1,69 @@
+pub mod checksum;
 pub mod color;
+pub mod deflate;
+pub mod display;
+pub mod framebuffer;
+pub mod hdr;
+pub mod pfm;
+pub mod png;
 pub mod ppm;
 
+use std::{
+    io::{self, Read, Write},
+    path::Path,
+};
+
 pub use color::Color;
+pub use display::DisplayTransform;
+pub use framebuffer::Framebuffer;
+
+/// Output file formats, picked from the file extension. PPM is written in the
+/// binary P6 variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+    /// Radiance RGBE
+    Hdr,
+    /// Portable Float Map
+    Pfm,
+}
+
+impl ImageFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "ppm" => Some(ImageFormat::Ppm),
+            "png" => Some(ImageFormat::Png),
+            "hdr" => Some(ImageFormat::Hdr),
+            "pfm" => Some(ImageFormat::Pfm),
+            _ => None,
+        }
+    }
+
+    /// formats storing unclamped linear radiance, they skip the display transform
+    pub fn is_high_dynamic_range(&self) -> bool {
+        matches!(self, ImageFormat::Hdr | ImageFormat::Pfm)
+    }
+
+    pub fn write<W: Write>(&self, image: &Framebuffer, writer: &mut W) -> io::Result<()> {
+        match self {
+            ImageFormat::Ppm => ppm::write_binary(image, writer),
+            ImageFormat::Png => png::write(image, writer),
+            ImageFormat::Hdr => hdr::write(image, writer),
+            ImageFormat::Pfm => pfm::write(image, writer),
+        }
+    }
+
+    pub fn read<R: Read>(&self, reader: &mut R) -> io::Result<Framebuffer> {
+        match self {
+            ImageFormat::Ppm => ppm::read(reader),
+            ImageFormat::Hdr => hdr::read(reader),
+            ImageFormat::Pfm => pfm::read(reader),
+            ImageFormat::Png => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "reading PNG images is not supported",
+            )),
+        }
+    }
+}
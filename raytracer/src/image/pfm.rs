@@ -0,0 +1,151 @@
+use std::io::{self, Read, Write};
+
+use crate::image::{Color, Framebuffer};
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Write the image as a color Portable Float Map, little endian 32 bit floats
+/// with the rows stored bottom to top
+pub fn write<W: Write>(image: &Framebuffer, writer: &mut W) -> io::Result<()> {
+    // a negative scale marks little endian data
+    write!(writer, "PF\n{} {}\n-1.0\n", image.width(), image.height())?;
+
+    let width = usize::from(image.width()).max(1);
+    let mut bytes = Vec::with_capacity(image.pixels().len() * 12);
+    for row in image.pixels().chunks(width).rev() {
+        for color in row {
+            for c in [color.r, color.g, color.b] {
+                bytes.extend_from_slice(&(c as f32).to_le_bytes());
+            }
+        }
+    }
+    writer.write_all(&bytes)
+}
+
+/// Read a color (PF) or grayscale (Pf) Portable Float Map in either byte order
+pub fn read<R: Read>(reader: &mut R) -> io::Result<Framebuffer> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let channels = match data.get(..2) {
+        Some(b"PF") => 3,
+        Some(b"Pf") => 1,
+        _ => return Err(invalid_data("not a PF or Pf float map")),
+    };
+
+    // the header is three whitespace separated values after the magic, and a
+    // single whitespace byte before the raster
+    let mut position = 2;
+    let mut fields = Vec::with_capacity(3);
+    while fields.len() < 3 {
+        while data.get(position).is_some_and(u8::is_ascii_whitespace) {
+            position += 1;
+        }
+        let start = position;
+        while data.get(position).is_some_and(|b| !b.is_ascii_whitespace()) {
+            position += 1;
+        }
+        if start == position {
+            return Err(invalid_data("header is truncated"));
+        }
+        fields.push(String::from_utf8_lossy(&data[start..position]).into_owned());
+    }
+    position += 1;
+
+    let (Ok(width), Ok(height), Ok(scale)) = (
+        fields[0].parse::<u16>(),
+        fields[1].parse::<u16>(),
+        fields[2].parse::<f64>(),
+    ) else {
+        return Err(invalid_data(format!("bad header '{}'", fields.join(" "))));
+    };
+    if scale == 0.0 {
+        return Err(invalid_data("scale must not be zero"));
+    }
+    let little_endian = scale < 0.0;
+
+    let w = usize::from(width);
+    let count = w * usize::from(height) * channels;
+    let raster = data
+        .get(position..position + count * 4)
+        .ok_or_else(|| invalid_data("raster data is truncated"))?;
+
+    let samples: Vec<f64> = raster
+        .chunks_exact(4)
+        .map(|b| {
+            let b = [b[0], b[1], b[2], b[3]];
+            f64::from(if little_endian {
+                f32::from_le_bytes(b)
+            } else {
+                f32::from_be_bytes(b)
+            })
+        })
+        .collect();
+
+    let mut pixels = Vec::with_capacity(w * usize::from(height));
+    for row in samples.chunks(w.max(1) * channels).rev() {
+        for p in row.chunks(channels) {
+            pixels.push(match channels {
+                1 => Color::new(p[0], p[0], p[0]),
+                _ => Color::new(p[0], p[1], p[2]),
+            });
+        }
+    }
+
+    Ok(Framebuffer::from_pixels(width, height, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let image = Framebuffer::from_pixels(
+            2,
+            2,
+            vec![
+                Color::new(0.5, 1.5, 100.0),
+                Color::new(0.0, 0.25, 3.0),
+                Color::new(8.0, 0.125, 0.0),
+                Color::new(1.0, 2.0, 4.0),
+            ],
+        );
+
+        let mut out = Vec::new();
+        write(&image, &mut out).unwrap();
+        assert!(out.starts_with(b"PF\n2 2\n-1.0\n"));
+        assert_eq!(read(&mut out.as_slice()).unwrap(), image);
+    }
+
+    #[test]
+    fn bottom_row_first() {
+        let image = Framebuffer::from_pixels(
+            1,
+            2,
+            vec![Color::new(1.0, 1.0, 1.0), Color::new(2.0, 2.0, 2.0)],
+        );
+        let mut out = Vec::new();
+        write(&image, &mut out).unwrap();
+        // the first stored pixel is the bottom one
+        assert_eq!(out[12..16], 2.0f32.to_le_bytes());
+    }
+
+    #[test]
+    fn big_endian_gray() {
+        let mut data = b"Pf\n2 1\n1.0\n".to_vec();
+        data.extend_from_slice(&0.5f32.to_be_bytes());
+        data.extend_from_slice(&4.0f32.to_be_bytes());
+        let image = read(&mut data.as_slice()).unwrap();
+        assert_eq!(image.get(0, 0), Color::new(0.5, 0.5, 0.5));
+        assert_eq!(image.get(1, 0), Color::new(4.0, 4.0, 4.0));
+    }
+
+    #[test]
+    fn truncated() {
+        let data = b"PF\n1 1\n-1.0\n\0\0";
+        assert!(read(&mut data.as_slice()).is_err());
+    }
+}
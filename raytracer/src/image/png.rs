@@ -0,0 +1,196 @@
+use std::{io, io::Write};
+
+use crate::image::{Color, Framebuffer, checksum::Crc32, deflate};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+const BYTES_PER_PIXEL: usize = 3;
+
+fn write_chunk<W: Write>(kind: &[u8; 4], data: &[u8], writer: &mut W) -> io::Result<()> {
+    writer.write_all(&(data.len() as u32).to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+
+    // the CRC covers the chunk type and data but not the length
+    let mut crc = Crc32::new();
+    crc.update(kind);
+    crc.update(data);
+    writer.write_all(&crc.finish().to_be_bytes())
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = i16::from(a) + i16::from(b) - i16::from(c);
+    let pa = (p - i16::from(a)).abs();
+    let pb = (p - i16::from(b)).abs();
+    let pc = (p - i16::from(c)).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// Apply PNG filter type to row given the unfiltered row above it
+fn filter_row(filter: u8, row: &[u8], above: &[u8], out: &mut Vec<u8>) {
+    out.push(filter);
+    for i in 0..row.len() {
+        let left = if i >= BYTES_PER_PIXEL {
+            row[i - BYTES_PER_PIXEL]
+        } else {
+            0
+        };
+        let up = above[i];
+        let up_left = if i >= BYTES_PER_PIXEL {
+            above[i - BYTES_PER_PIXEL]
+        } else {
+            0
+        };
+
+        let predicted = match filter {
+            0 => 0,
+            1 => left,
+            2 => up,
+            3 => ((u16::from(left) + u16::from(up)) / 2) as u8,
+            _ => paeth(left, up, up_left),
+        };
+        out.push(row[i].wrapping_sub(predicted));
+    }
+}
+
+/// Filter every scanline, picking per row the filter whose output has the
+/// smallest sum of absolute values as signed bytes, which tends to compress best
+fn filter_scanlines(width: usize, pixels: &[Color<u8>]) -> Vec<u8> {
+    let stride = width * BYTES_PER_PIXEL;
+    let raw: Vec<u8> = pixels.iter().flat_map(|c| [c.r, c.g, c.b]).collect();
+    let zero_row = vec![0u8; stride];
+
+    let mut out = Vec::with_capacity(raw.len() + raw.len() / stride.max(1));
+    let mut candidate = Vec::with_capacity(stride + 1);
+
+    for (y, row) in raw.chunks(stride).enumerate() {
+        let above = if y == 0 {
+            &zero_row[..]
+        } else {
+            &raw[(y - 1) * stride..y * stride]
+        };
+
+        let mut best: Option<(u64, Vec<u8>)> = None;
+        for filter in 0..=4 {
+            candidate.clear();
+            filter_row(filter, row, above, &mut candidate);
+            let score = candidate[1..]
+                .iter()
+                .map(|&b| u64::from((b as i8).unsigned_abs()))
+                .sum();
+            if best
+                .as_ref()
+                .is_none_or(|(best_score, _)| score < *best_score)
+            {
+                best = Some((score, candidate.clone()));
+            }
+        }
+
+        if let Some((_, filtered)) = best {
+            out.extend(filtered);
+        }
+    }
+
+    out
+}
+
+/// Write the image as an 8 bit truecolor PNG, which can not be empty
+pub fn write<W: Write>(image: &Framebuffer, writer: &mut W) -> io::Result<()> {
+    if image.width() == 0 || image.height() == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "a {}x{} image can not be written as PNG",
+                image.width(),
+                image.height()
+            ),
+        ));
+    }
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&u32::from(image.width()).to_be_bytes());
+    ihdr.extend_from_slice(&u32::from(image.height()).to_be_bytes());
+    // bit depth 8, color type 2 (RGB), deflate, adaptive filtering, no interlace
+    ihdr.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+    let scanlines = filter_scanlines(usize::from(image.width()), &image.to_u8());
+    let image_data = deflate::zlib_compress(&scanlines);
+
+    writer.write_all(&SIGNATURE)?;
+    write_chunk(b"IHDR", &ihdr, writer)?;
+    write_chunk(b"IDAT", &image_data, writer)?;
+    write_chunk(b"IEND", &[], writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_and_header() {
+        let mut out = Vec::new();
+        write(&Framebuffer::new(4, 3), &mut out).unwrap();
+        assert_eq!(out[..8], SIGNATURE);
+        assert_eq!(&out[12..16], b"IHDR");
+        assert_eq!(out[16..24], [0, 0, 0, 4, 0, 0, 0, 3]);
+    }
+
+    #[test]
+    fn empty_image() {
+        for (width, height) in [(0, 3), (3, 0)] {
+            let mut out = Vec::new();
+            let err = write(&Framebuffer::new(width, height), &mut out).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+            assert!(out.is_empty());
+        }
+    }
+
+    #[test]
+    fn paeth_predictor() {
+        assert_eq!(paeth(10, 20, 10), 20);
+        assert_eq!(paeth(20, 10, 10), 20);
+        assert_eq!(paeth(10, 10, 20), 10);
+    }
+
+    #[test]
+    fn chunk_layout() {
+        let mut out = Vec::new();
+        write_chunk(b"IEND", &[], &mut out).unwrap();
+        // well known IEND chunk
+        assert_eq!(
+            out,
+            vec![0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]
+        );
+    }
+
+    #[test]
+    fn filters_are_reversible() {
+        let row = [10u8, 200, 30, 40, 50, 60];
+        let above = [5u8, 100, 255, 0, 80, 70];
+
+        for filter in 0..=4 {
+            let mut out = Vec::new();
+            filter_row(filter, &row, &above, &mut out);
+
+            let mut decoded = [0u8; 6];
+            for i in 0..row.len() {
+                let left = if i >= 3 { decoded[i - 3] } else { 0 };
+                let up_left = if i >= 3 { above[i - 3] } else { 0 };
+                let predicted = match filter {
+                    0 => 0,
+                    1 => left,
+                    2 => above[i],
+                    3 => ((u16::from(left) + u16::from(above[i])) / 2) as u8,
+                    _ => paeth(left, above[i], up_left),
+                };
+                decoded[i] = out[i + 1].wrapping_add(predicted);
+            }
+            assert_eq!(decoded, row, "filter {filter}");
+        }
+    }
+}
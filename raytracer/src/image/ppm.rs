@@ -1,13 +1,199 @@
-use std::{fs::File, io, io::Write};
+use std::io::{self, Read, Write};
 
-use crate::image::Color;
+use crate::image::{Color, Framebuffer};
 
-pub fn write_header(width: u16, height: u16, file: &mut File) -> io::Result<()> {
-    write!(file, "P3\n{} {}\n255\n", width, height)?;
+pub fn write_header<W: Write>(width: u16, height: u16, writer: &mut W) -> io::Result<()> {
+    write!(writer, "P3\n{} {}\n255\n", width, height)?;
     Ok(())
 }
 
-pub fn write_color(color: &Color<u8>, file: &mut File) -> io::Result<()> {
-    write!(file, "{} {} {}\n", color.r, color.g, color.b)?;
+pub fn write_color<W: Write>(color: &Color<u8>, writer: &mut W) -> io::Result<()> {
+    writeln!(writer, "{} {} {}", color.r, color.g, color.b)?;
     Ok(())
 }
+
+/// Write the image as ASCII PPM (P3)
+pub fn write<W: Write>(image: &Framebuffer, writer: &mut W) -> io::Result<()> {
+    write_header(image.width(), image.height(), writer)?;
+    for color in image.to_u8().iter() {
+        write_color(color, writer)?;
+    }
+    Ok(())
+}
+
+/// Write the image as binary PPM (P6), 3 bytes per pixel
+pub fn write_binary<W: Write>(image: &Framebuffer, writer: &mut W) -> io::Result<()> {
+    write!(writer, "P6\n{} {}\n255\n", image.width(), image.height())?;
+    let bytes: Vec<u8> = image.to_u8().iter().flat_map(|c| [c.r, c.g, c.b]).collect();
+    writer.write_all(&bytes)
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Cursor over the file contents for the netpbm header and ASCII rasters
+struct Parser<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// skip whitespace and `#` comments running to the end of the line
+    fn skip_whitespace(&mut self) {
+        while let Some(&c) = self.data.get(self.position) {
+            if c == b'#' {
+                while self.data.get(self.position).is_some_and(|&c| c != b'\n') {
+                    self.position += 1;
+                }
+            } else if c.is_ascii_whitespace() {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn unsigned(&mut self, what: &str) -> io::Result<u32> {
+        self.skip_whitespace();
+        let start = self.position;
+        while self.data.get(self.position).is_some_and(u8::is_ascii_digit) {
+            self.position += 1;
+        }
+
+        std::str::from_utf8(&self.data[start..self.position])
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| invalid_data(format!("expected {what} at byte {start}")))
+    }
+}
+
+/// Read a P2/P3 (ASCII) or P5/P6 (binary) grayscale or color netpbm image.
+/// Samples are scaled by maxval to [0, 1] as stored, no transfer function is
+/// undone, and grayscale is copied to all three channels.
+pub fn read<R: Read>(reader: &mut R) -> io::Result<Framebuffer> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let (channels, binary) = match data.get(..2) {
+        Some(b"P2") => (1, false),
+        Some(b"P3") => (3, false),
+        Some(b"P5") => (1, true),
+        Some(b"P6") => (3, true),
+        _ => return Err(invalid_data("not a P2, P3, P5 or P6 netpbm image")),
+    };
+
+    let mut parser = Parser {
+        data: &data,
+        position: 2,
+    };
+    let width = parser.unsigned("width")?;
+    let height = parser.unsigned("height")?;
+    let maxval = parser.unsigned("maxval")?;
+
+    if !(1..=65535).contains(&maxval) {
+        return Err(invalid_data(format!(
+            "maxval {maxval} out of range 1..=65535"
+        )));
+    }
+    let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+        return Err(invalid_data(format!("{width}x{height} image is too large")));
+    };
+
+    let count = usize::from(width) * usize::from(height) * channels;
+    let mut samples = Vec::with_capacity(count);
+
+    if binary {
+        // a single whitespace character separates the header from the raster
+        parser.position += 1;
+        let raster = data.get(parser.position..).unwrap_or_default();
+        let bytes_per_sample = if maxval < 256 { 1 } else { 2 };
+        if raster.len() < count * bytes_per_sample {
+            return Err(invalid_data("raster data is truncated"));
+        }
+
+        if bytes_per_sample == 1 {
+            samples.extend(raster[..count].iter().map(|&b| u32::from(b)));
+        } else {
+            samples.extend(
+                raster[..count * 2]
+                    .chunks_exact(2)
+                    .map(|b| u32::from(u16::from_be_bytes([b[0], b[1]]))),
+            );
+        }
+    } else {
+        for _ in 0..count {
+            samples.push(parser.unsigned("sample")?);
+        }
+    }
+
+    let scale = 1.0 / f64::from(maxval);
+    let sample = |i: usize| f64::from(samples[i].min(maxval)) * scale;
+    let pixels = (0..usize::from(width) * usize::from(height))
+        .map(|p| match channels {
+            1 => Color::new(sample(p), sample(p), sample(p)),
+            _ => Color::new(sample(3 * p), sample(3 * p + 1), sample(3 * p + 2)),
+        })
+        .collect();
+
+    Ok(Framebuffer::from_pixels(width, height, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::color;
+
+    fn test_image() -> Framebuffer {
+        Framebuffer::from_pixels(
+            2,
+            2,
+            vec![color::RED, color::GREEN, color::BLUE, color::BLACK],
+        )
+    }
+
+    #[test]
+    fn ascii_round_trip() {
+        let mut out = Vec::new();
+        write(&test_image(), &mut out).unwrap();
+        assert_eq!(read(&mut out.as_slice()).unwrap(), test_image());
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let mut out = Vec::new();
+        write_binary(&test_image(), &mut out).unwrap();
+        assert!(out.starts_with(b"P6\n2 2\n255\n"));
+        assert_eq!(out.len(), 11 + 12);
+        assert_eq!(read(&mut out.as_slice()).unwrap(), test_image());
+    }
+
+    #[test]
+    fn ascii_gray_with_comments() {
+        let data = b"P2\n# made by hand\n2 1 # size\n15\n0 15\n";
+        let image = read(&mut data.as_slice()).unwrap();
+        assert_eq!(image.get(0, 0), color::BLACK);
+        assert_eq!(image.get(1, 0), Color::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn binary_gray_16_bit() {
+        let mut data = b"P5 2 1 65535\n".to_vec();
+        data.extend_from_slice(&[0xFF, 0xFF, 0x00, 0x00]);
+        let image = read(&mut data.as_slice()).unwrap();
+        assert_eq!(image.get(0, 0), Color::new(1.0, 1.0, 1.0));
+        assert_eq!(image.get(1, 0), color::BLACK);
+    }
+
+    #[test]
+    fn truncated() {
+        let data = b"P6 2 2 255\n\x00\x00\x00";
+        let err = read(&mut data.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn unknown_magic() {
+        assert!(read(&mut b"P7 1 1 255\n".as_slice()).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug)]
 pub struct Interval {
     pub min: f64,
     pub max: f64,
@@ -9,6 +9,11 @@ impl Interval {
         Interval { min, max }
     }
 
+    /// smallest interval containing both a and b
+    pub fn enclosing(a: &Interval, b: &Interval) -> Interval {
+        Interval::new(a.min.min(b.min), a.max.max(b.max))
+    }
+
     pub fn size(&self) -> f64 {
         self.max - self.min
     }
@@ -23,6 +28,12 @@ impl Interval {
         self.min < x && x < self.max
     }
 
+    /// grow the interval by delta, half on each side
+    pub fn expand(&self, delta: f64) -> Interval {
+        let padding = delta / 2.0;
+        Interval::new(self.min - padding, self.max + padding)
+    }
+
     /// clamp x in the range of this interval
     pub fn clamp(&self, x: f64) -> f64 {
         if x < self.min {
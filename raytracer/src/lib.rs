@@ -2,5 +2,9 @@ pub mod camera;
 pub mod geometry;
 pub mod image;
 pub mod interval;
+pub mod loader;
+pub mod materials;
 pub mod math;
+pub mod scene;
 pub mod shapes;
+pub mod textures;
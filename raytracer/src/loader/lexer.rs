@@ -0,0 +1,246 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenKind {
+    Ident(String),
+    Number(f64),
+    /// double quoted text, used for file paths
+    Str(String),
+    LBrace,
+    RBrace,
+    Slash,
+    Eof,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Ident(name) => write!(f, "'{name}'"),
+            TokenKind::Number(n) => write!(f, "number {n}"),
+            TokenKind::Str(text) => write!(f, "string \"{text}\""),
+            TokenKind::LBrace => write!(f, "'{{'"),
+            TokenKind::RBrace => write!(f, "'}}'"),
+            TokenKind::Slash => write!(f, "'/'"),
+            TokenKind::Eof => write!(f, "end of file"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// 1 based line of the first character
+    pub line: usize,
+    /// 1 based column of the first character
+    pub column: usize,
+}
+
+/// Error with the position in the source where it happened
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    pub fn at(token: &Token, message: impl Into<String>) -> Self {
+        Self::new(token.line, token.column, message)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Split the source into tokens, `#` starts a comment that runs to the end of the line
+pub fn tokenize(source: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+    let mut column = 1;
+
+    while let Some(&c) = chars.peek() {
+        let (start_line, start_column) = (line, column);
+
+        if c == '\n' {
+            chars.next();
+            line += 1;
+            column = 1;
+            continue;
+        }
+
+        if c.is_whitespace() {
+            chars.next();
+            column += 1;
+            continue;
+        }
+
+        if c == '#' {
+            while chars.peek().is_some_and(|&c| c != '\n') {
+                chars.next();
+            }
+            continue;
+        }
+
+        let kind = match c {
+            '{' | '}' | '/' => {
+                chars.next();
+                column += 1;
+                match c {
+                    '{' => TokenKind::LBrace,
+                    '}' => TokenKind::RBrace,
+                    _ => TokenKind::Slash,
+                }
+            }
+            '"' => {
+                chars.next();
+                column += 1;
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => {
+                            column += 1;
+                            break;
+                        }
+                        Some('\n') | None => {
+                            return Err(ParseError::new(
+                                start_line,
+                                start_column,
+                                "unterminated string",
+                            ));
+                        }
+                        Some(c) => {
+                            text.push(c);
+                            column += 1;
+                        }
+                    }
+                }
+                TokenKind::Str(text)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut ident = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    ident.push(c);
+                    chars.next();
+                    column += 1;
+                }
+                TokenKind::Ident(ident)
+            }
+            c if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' => {
+                let mut text = String::new();
+                while let Some(&c) = chars.peek() {
+                    let sign =
+                        (c == '-' || c == '+') && (text.is_empty() || text.ends_with(['e', 'E']));
+                    if !(c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || sign) {
+                        break;
+                    }
+                    text.push(c);
+                    chars.next();
+                    column += 1;
+                }
+                let number = text.parse::<f64>().map_err(|_| {
+                    ParseError::new(start_line, start_column, format!("invalid number '{text}'"))
+                })?;
+                TokenKind::Number(number)
+            }
+            c => {
+                return Err(ParseError::new(
+                    line,
+                    column,
+                    format!("unexpected character '{c}'"),
+                ));
+            }
+        };
+
+        tokens.push(Token {
+            kind,
+            line: start_line,
+            column: start_column,
+        });
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::Eof,
+        line,
+        column,
+    });
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(source: &str) -> Vec<TokenKind> {
+        tokenize(source)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.kind)
+            .collect()
+    }
+
+    #[test]
+    fn tokens_and_comments() {
+        assert_eq!(
+            kinds("sphere { radius -0.5 } # comment\n16/9 1e-3"),
+            vec![
+                TokenKind::Ident("sphere".into()),
+                TokenKind::LBrace,
+                TokenKind::Ident("radius".into()),
+                TokenKind::Number(-0.5),
+                TokenKind::RBrace,
+                TokenKind::Number(16.0),
+                TokenKind::Slash,
+                TokenKind::Number(9.0),
+                TokenKind::Number(1e-3),
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn positions() {
+        let tokens = tokenize("a\n  b").unwrap();
+        assert_eq!((tokens[0].line, tokens[0].column), (1, 1));
+        assert_eq!((tokens[1].line, tokens[1].column), (2, 3));
+    }
+
+    #[test]
+    fn strings() {
+        let tokens = tokenize("file \"models/a b.obj\" x").unwrap();
+        assert_eq!(tokens[1].kind, TokenKind::Str("models/a b.obj".into()));
+        assert_eq!(tokens[2].column, 23);
+
+        let err = tokenize("file \"oops\n").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 6));
+    }
+
+    #[test]
+    fn bad_number() {
+        let err = tokenize("x 1.2.3").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+    }
+
+    #[test]
+    fn bad_character() {
+        let err = tokenize("\n  @").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::loader::lexer::ParseError;
+
+/// One line of a line based format like OBJ or MTL, split into whitespace
+/// separated words. Everything after `#` is a comment
+pub struct Line<'a> {
+    text: &'a str,
+    number: usize,
+    /// words with their 0 based byte offsets
+    words: Vec<(usize, &'a str)>,
+    position: usize,
+}
+
+impl<'a> Line<'a> {
+    /// number is the 1 based line number used in errors
+    pub fn new(text: &'a str, number: usize) -> Self {
+        let text = text.split('#').next().unwrap_or_default();
+        let words = text
+            .split_ascii_whitespace()
+            .map(|word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+            .collect();
+
+        Self {
+            text,
+            number,
+            words,
+            position: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// 1 based column of the next word, or the end of the line when there is none
+    fn column(&self) -> usize {
+        self.words
+            .get(self.position)
+            .map_or(self.text.trim_end().len(), |&(offset, _)| offset)
+            + 1
+    }
+
+    /// Error pointing at the next word
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(self.number, self.column(), message)
+    }
+
+    /// Error pointing at the previous word
+    pub fn error_before(&self, message: impl Into<String>) -> ParseError {
+        let offset = self.words[self.position.saturating_sub(1)].0;
+        ParseError::new(self.number, offset + 1, message)
+    }
+
+    pub fn peek(&self) -> Option<&'a str> {
+        self.words.get(self.position).map(|&(_, word)| word)
+    }
+
+    pub fn word(&mut self) -> Result<&'a str, ParseError> {
+        let word = self
+            .peek()
+            .ok_or_else(|| self.error("unexpected end of line"))?;
+        self.position += 1;
+        Ok(word)
+    }
+
+    pub fn number(&mut self) -> Result<f64, ParseError> {
+        let word = self.word()?;
+        word.parse()
+            .map_err(|_| self.error_before(format!("invalid number '{word}'")))
+    }
+
+    /// The rest of the line as written, for names that may contain spaces
+    pub fn rest(&mut self) -> Result<&'a str, ParseError> {
+        let &(offset, _) = self
+            .words
+            .get(self.position)
+            .ok_or_else(|| self.error("unexpected end of line"))?;
+        self.position = self.words.len();
+        Ok(self.text[offset..].trim_end())
+    }
+
+    /// Fail if anything is left on the line
+    pub fn end(&self) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(word) => Err(self.error(format!("unexpected '{word}'"))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Lines of source with their line numbers, blank and comment lines skipped
+pub fn lines(source: &str) -> impl Iterator<Item = Line<'_>> {
+    source
+        .lines()
+        .enumerate()
+        .map(|(i, text)| Line::new(text, i + 1))
+        .filter(|line| !line.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_and_columns() {
+        let mut line = Line::new("v  1 -2.5 x # comment", 3);
+        assert_eq!(line.word().unwrap(), "v");
+        assert_eq!(line.number().unwrap(), 1.0);
+        assert_eq!(line.number().unwrap(), -2.5);
+
+        let err = line.number().unwrap_err();
+        assert_eq!((err.line, err.column), (3, 11));
+
+        let err = line.word().unwrap_err();
+        assert_eq!(err.column, 12);
+    }
+
+    #[test]
+    fn rest_keeps_spaces() {
+        let mut line = Line::new("mtllib my model.mtl  ", 1);
+        line.word().unwrap();
+        assert_eq!(line.rest().unwrap(), "my model.mtl");
+        assert!(line.end().is_ok());
+    }
+
+    #[test]
+    fn skips_blank_lines() {
+        let numbers: Vec<usize> = lines("# header\n\nv 1 2 3\n  \nf 1 2 3")
+            .map(|line| line.number)
+            .collect();
+        assert_eq!(numbers, vec![3, 5]);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod lexer;
+pub mod line;
+pub mod mtl;
+pub mod obj;
+pub mod scene_file;
+
+pub use lexer::ParseError;
@@ -0,0 +1,198 @@
+//! Wavefront MTL material libraries, as referenced by OBJ files
+use std::{fs, io, path::Path};
+
+use crate::{
+    image::Color,
+    loader::{
+        lexer::ParseError,
+        line::{self, Line},
+    },
+    materials::{
+        dielectric::Dielectric, diffuse_light::DiffuseLight, lambertian::Lambertian,
+        material::Materials, metal::Metal,
+    },
+};
+
+/// Index of refraction used for transparent materials without `Ni`
+const DEFAULT_IOR: f64 = 1.5;
+
+/// Material as written in an MTL file, before it is mapped to one of ours
+#[derive(Debug, Clone, PartialEq)]
+pub struct MtlMaterial {
+    pub name: String,
+    /// `Kd`
+    pub diffuse: Color<f64>,
+    /// `Ks`
+    pub specular: Color<f64>,
+    /// `Ns`, 0 to 1000
+    pub shininess: f64,
+    /// `Ni`
+    pub ior: Option<f64>,
+    /// `Ke`
+    pub emission: Color<f64>,
+    /// `d`, or 1 - `Tr`
+    pub dissolve: f64,
+    /// `illum`
+    pub illum: u32,
+}
+
+impl MtlMaterial {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            diffuse: Color::new(0.8, 0.8, 0.8),
+            specular: Color::new(0.0, 0.0, 0.0),
+            shininess: 0.0,
+            ior: None,
+            emission: Color::new(0.0, 0.0, 0.0),
+            dissolve: 1.0,
+            illum: 2,
+        }
+    }
+
+    /// Closest raytracer material: emissive materials become lights,
+    /// transparent ones dielectrics, ones whose specular color outweighs the
+    /// diffuse one become metals with a fuzz derived from the shininess,
+    /// everything else is lambertian
+    pub fn to_material(&self) -> Materials {
+        let max = |c: Color<f64>| c.r.max(c.g).max(c.b);
+
+        if max(self.emission) > 0.0 {
+            Materials::DiffuseLight(DiffuseLight::new(self.emission))
+        } else if self.dissolve < 1.0 || matches!(self.illum, 4 | 6 | 7 | 9) {
+            Materials::Dielectric(Dielectric::new(self.ior.unwrap_or(DEFAULT_IOR)))
+        } else if max(self.specular) > 0.0
+            && (self.illum == 3 || max(self.specular) > max(self.diffuse))
+        {
+            let fuzz = (2.0 / (self.shininess.max(0.0) + 2.0)).sqrt();
+            Materials::Metal(Metal::new(self.specular, fuzz))
+        } else {
+            Materials::Lambertian(Lambertian::new(self.diffuse))
+        }
+    }
+}
+
+/// Read and parse the material library at path
+pub fn load(path: impl AsRef<Path>) -> io::Result<Vec<MtlMaterial>> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+    parse(&source).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}:{}", path.display(), e),
+        )
+    })
+}
+
+/// Parse a material library, unsupported statements are ignored
+pub fn parse(source: &str) -> Result<Vec<MtlMaterial>, ParseError> {
+    let mut materials: Vec<MtlMaterial> = Vec::new();
+
+    for mut line in line::lines(source) {
+        let keyword = line.word()?;
+
+        if keyword == "newmtl" {
+            materials.push(MtlMaterial::new(line.rest()?));
+            continue;
+        }
+
+        let Some(material) = materials.last_mut() else {
+            return Err(line.error_before(format!("'{keyword}' before any 'newmtl'")));
+        };
+
+        match keyword {
+            "Kd" => material.diffuse = color(&mut line)?,
+            "Ks" => material.specular = color(&mut line)?,
+            "Ke" => material.emission = color(&mut line)?,
+            "Ns" => material.shininess = line.number()?,
+            "Ni" => material.ior = Some(line.number()?),
+            "d" => material.dissolve = line.number()?,
+            "Tr" => material.dissolve = 1.0 - line.number()?,
+            "illum" => {
+                let value = line.number()?;
+                if value < 0.0 || value.fract() != 0.0 {
+                    return Err(line.error_before(format!("invalid illumination model {value}")));
+                }
+                material.illum = value as u32;
+            }
+            _ => continue,
+        }
+
+        line.end()?;
+    }
+
+    Ok(materials)
+}
+
+/// `r g b`, a single value is used for all three channels
+fn color(line: &mut Line) -> Result<Color<f64>, ParseError> {
+    if line.peek() == Some("spectral") || line.peek() == Some("xyz") {
+        return Err(line.error("only rgb colors are supported"));
+    }
+
+    let r = line.number()?;
+    if line.peek().is_none() {
+        return Ok(Color::new(r, r, r));
+    }
+    Ok(Color::new(r, line.number()?, line.number()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_properties() {
+        let materials = parse(
+            "# library\n\
+             newmtl red paint\n\
+             Kd 0.8 0.1 0.1\n\
+             Ks 0.2\n\
+             Ns 250\n\
+             Ke 1 2 3\n\
+             map_Kd red.png\n\
+             newmtl glass\n\
+             Ni 1.33\n\
+             d 0.2\n",
+        )
+        .unwrap();
+
+        assert_eq!(materials.len(), 2);
+        let red = &materials[0];
+        assert_eq!(red.name, "red paint");
+        assert_eq!(red.diffuse, Color::new(0.8, 0.1, 0.1));
+        assert_eq!(red.specular, Color::new(0.2, 0.2, 0.2));
+        assert_eq!(red.shininess, 250.0);
+        assert_eq!(red.emission, Color::new(1.0, 2.0, 3.0));
+        assert_eq!(materials[1].ior, Some(1.33));
+        assert_eq!(materials[1].dissolve, 0.2);
+    }
+
+    #[test]
+    fn maps_to_materials() {
+        let mut material = MtlMaterial::new("m");
+        assert!(matches!(material.to_material(), Materials::Lambertian(_)));
+
+        material.specular = Color::new(0.9, 0.9, 0.9);
+        assert!(matches!(material.to_material(), Materials::Metal(_)));
+
+        material.illum = 7;
+        assert!(matches!(material.to_material(), Materials::Dielectric(_)));
+
+        material.emission = Color::new(2.0, 2.0, 2.0);
+        assert!(matches!(material.to_material(), Materials::DiffuseLight(_)));
+    }
+
+    #[test]
+    fn property_before_newmtl() {
+        let err = parse("\n  Kd 1 1 1").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+
+    #[test]
+    fn trailing_values() {
+        let err = parse("newmtl a\nNs 10 20").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 7));
+    }
+}
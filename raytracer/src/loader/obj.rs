@@ -0,0 +1,320 @@
+//! Wavefront OBJ models. Supports vertices (`v`, `vt`, `vn`), polygonal faces
+//! (`f`) which are triangulated as fans, groups (`g`, `o`) and materials from
+//! MTL libraries (`mtllib`, `usemtl`). Other statements are ignored
+use std::{fs, io, path::Path};
+
+use crate::{
+    loader::{
+        lexer::ParseError,
+        line::{self, Line},
+        mtl::{self, MtlMaterial},
+    },
+    materials::material::Materials,
+    math::Vector3f,
+    shapes::mesh::{Face, Group, MeshData},
+};
+
+/// Parsed OBJ file whose materials are still only names
+#[derive(Debug)]
+pub struct ObjFile {
+    /// mesh without materials, face materials index into material_names
+    pub data: MeshData,
+    /// `usemtl` names in order of first use, None for faces before any `usemtl`
+    pub material_names: Vec<Option<String>>,
+    /// `mtllib` files as written, relative to the OBJ file
+    pub libraries: Vec<String>,
+}
+
+impl ObjFile {
+    /// Resolve the material names against library, names it does not define
+    /// and faces without a material use default
+    pub fn into_mesh(self, library: &[MtlMaterial], default: &Materials) -> MeshData {
+        let mut data = self.data;
+        data.materials = self
+            .material_names
+            .iter()
+            .map(|name| {
+                name.as_ref()
+                    .and_then(|name| library.iter().rev().find(|m| &m.name == name))
+                    .map_or_else(|| default.clone(), MtlMaterial::to_material)
+            })
+            .collect();
+        data
+    }
+}
+
+/// Read the OBJ file at path along with its material libraries, faces without
+/// a known material use default
+pub fn load(path: impl AsRef<Path>, default: &Materials) -> io::Result<MeshData> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+    let obj = parse(&source).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}:{}", path.display(), e),
+        )
+    })?;
+
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let mut library = Vec::new();
+    for name in &obj.libraries {
+        library.extend(mtl::load(directory.join(name))?);
+    }
+
+    Ok(obj.into_mesh(&library, default))
+}
+
+pub fn parse(source: &str) -> Result<ObjFile, ParseError> {
+    let mut data = MeshData::default();
+    let mut material_names = Vec::new();
+    let mut libraries = Vec::new();
+    let mut material = None;
+    let mut group: Option<(String, usize)> = None;
+
+    for mut line in line::lines(source) {
+        match line.word()? {
+            "v" => {
+                // an optional w or vertex color may follow
+                data.positions.push(vector(&mut line)?);
+                continue;
+            }
+            "vn" => data.normals.push(vector(&mut line)?),
+            "vt" => {
+                let u = line.number()?;
+                let v = if line.peek().is_some() {
+                    line.number()?
+                } else {
+                    0.0
+                };
+                data.uvs.push((u, v));
+                // optional depth
+                continue;
+            }
+            "f" => {
+                let material = *material.get_or_insert_with(|| {
+                    material_names.push(None);
+                    material_names.len() - 1
+                });
+                face(&mut line, &mut data, material)?;
+            }
+            "g" | "o" => {
+                let name = if line.peek().is_some() {
+                    line.rest()?
+                } else {
+                    "default"
+                };
+                finish_group(&mut data, group.take());
+                group = Some((name.to_string(), data.faces.len()));
+            }
+            "usemtl" => {
+                let name = Some(line.rest()?.to_string());
+                material = Some(
+                    material_names
+                        .iter()
+                        .position(|n| *n == name)
+                        .unwrap_or_else(|| {
+                            material_names.push(name);
+                            material_names.len() - 1
+                        }),
+                );
+            }
+            "mtllib" => libraries.push(line.rest()?.to_string()),
+            _ => continue,
+        }
+
+        line.end()?;
+    }
+
+    finish_group(&mut data, group);
+
+    Ok(ObjFile {
+        data,
+        material_names,
+        libraries,
+    })
+}
+
+fn vector(line: &mut Line) -> Result<Vector3f, ParseError> {
+    Ok(Vector3f::new(
+        line.number()?,
+        line.number()?,
+        line.number()?,
+    ))
+}
+
+fn finish_group(data: &mut MeshData, group: Option<(String, usize)>) {
+    if let Some((name, start)) = group
+        && start < data.faces.len()
+    {
+        data.groups.push(Group {
+            name,
+            faces: start..data.faces.len(),
+        });
+    }
+}
+
+/// 1 based index, negative ones count back from the last element defined so far
+fn resolve(line: &Line, text: &str, len: usize, what: &str) -> Result<usize, ParseError> {
+    let index: i64 = text
+        .parse()
+        .map_err(|_| line.error_before(format!("invalid {what} index '{text}'")))?;
+
+    let resolved = if index < 0 {
+        len as i64 + index
+    } else {
+        index - 1
+    };
+    if index == 0 || resolved < 0 || resolved >= len as i64 {
+        return Err(line.error_before(format!(
+            "{what} index {index} out of range, {len} defined so far"
+        )));
+    }
+    Ok(resolved as usize)
+}
+
+/// `f v/vt/vn ...` with vt and vn optional, fan triangulated
+fn face(line: &mut Line, data: &mut MeshData, material: usize) -> Result<(), ParseError> {
+    let mut positions = Vec::new();
+    let mut uvs = Vec::new();
+    let mut normals = Vec::new();
+
+    while line.peek().is_some() {
+        let vertex = line.word()?;
+        let mut parts = vertex.split('/');
+
+        let position = parts.next().unwrap_or_default();
+        positions.push(resolve(line, position, data.positions.len(), "vertex")?);
+
+        match parts.next() {
+            Some("") | None => {}
+            Some(uv) => uvs.push(resolve(line, uv, data.uvs.len(), "texture coordinate")?),
+        }
+        match parts.next() {
+            Some("") | None => {}
+            Some(normal) => normals.push(resolve(line, normal, data.normals.len(), "normal")?),
+        }
+        if parts.next().is_some() {
+            return Err(line.error_before(format!("invalid face vertex '{vertex}'")));
+        }
+    }
+
+    if positions.len() < 3 {
+        return Err(line.error("a face needs at least 3 vertices"));
+    }
+    for (indices, what) in [(&uvs, "texture coordinates"), (&normals, "normals")] {
+        if !indices.is_empty() && indices.len() != positions.len() {
+            return Err(line.error_before(format!(
+                "{what} must be given for all vertices of a face or none"
+            )));
+        }
+    }
+
+    let fan = |indices: &[usize], i: usize| [indices[0], indices[i], indices[i + 1]];
+    for i in 1..positions.len() - 1 {
+        data.faces.push(Face {
+            positions: fan(&positions, i),
+            normals: (!normals.is_empty()).then(|| fan(&normals, i)),
+            uvs: (!uvs.is_empty()).then(|| fan(&uvs, i)),
+            material,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{image::color, materials::lambertian::Lambertian};
+
+    const QUAD: &str = "\
+        mtllib quad.mtl\n\
+        v 0 0 0\n\
+        v 1 0 0\n\
+        v 1 1 0 1.0\n\
+        v 0 1 0\n\
+        vt 0 0\n\
+        vt 1 0\n\
+        vt 1 1\n\
+        vt 0 1\n\
+        vn 0 0 1\n\
+        g front\n\
+        usemtl red\n\
+        f 1/1/1 2/2/1 3/3/1 4/4/1\n\
+        g back\n\
+        usemtl missing\n\
+        f -1 -2 -3\n";
+
+    fn default_material() -> Materials {
+        Materials::Lambertian(Lambertian::new(color::GREEN))
+    }
+
+    #[test]
+    fn fan_triangulation() {
+        let obj = parse(QUAD).unwrap();
+        let faces = &obj.data.faces;
+
+        assert_eq!(obj.libraries, vec!["quad.mtl"]);
+        assert_eq!(faces.len(), 3);
+        assert_eq!(faces[0].positions, [0, 1, 2]);
+        assert_eq!(faces[1].positions, [0, 2, 3]);
+        assert_eq!(faces[1].uvs, Some([0, 2, 3]));
+        assert_eq!(faces[1].normals, Some([0, 0, 0]));
+    }
+
+    #[test]
+    fn negative_indices() {
+        let obj = parse(QUAD).unwrap();
+        let face = &obj.data.faces[2];
+        assert_eq!(face.positions, [3, 2, 1]);
+        assert_eq!((face.uvs, face.normals), (None, None));
+    }
+
+    #[test]
+    fn groups_and_materials() {
+        let obj = parse(QUAD).unwrap();
+        assert_eq!(obj.data.groups.len(), 2);
+        assert_eq!(obj.data.groups[0].name, "front");
+        assert_eq!(obj.data.groups[0].faces, 0..2);
+        assert_eq!(obj.data.groups[1].faces, 2..3);
+
+        let mut red = MtlMaterial::new("red");
+        red.diffuse = color::RED;
+        let data = obj.into_mesh(&[red], &default_material());
+        assert!(data.validate().is_ok());
+        assert_eq!(data.materials.len(), 2);
+        assert_eq!(data.faces[0].material, 0);
+        assert_eq!(data.faces[2].material, 1);
+    }
+
+    #[test]
+    fn faces_without_material() {
+        let obj = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\nf 3 2 1").unwrap();
+        assert_eq!(obj.material_names, vec![None]);
+        let data = obj.into_mesh(&[], &default_material());
+        assert_eq!(data.materials.len(), 1);
+        assert!(data.groups.is_empty());
+    }
+
+    #[test]
+    fn index_out_of_range() {
+        let err = parse("v 0 0 0\nv 1 0 0\nf 1 2 3").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 7));
+
+        let err = parse("v 0 0 0\nf 0 1 1").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+
+    #[test]
+    fn mixed_vertex_formats() {
+        let err = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nf 1/1 2 3").unwrap_err();
+        assert_eq!(err.line, 5);
+    }
+
+    #[test]
+    fn too_few_vertices() {
+        let err = parse("v 0 0 0\nv 1 0 0\nf 1 2").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 6));
+    }
+}
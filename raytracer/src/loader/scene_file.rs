@@ -0,0 +1,1606 @@
+//! Text scene description, a list of statements each followed by a block of
+//! properties:
+//!
+//! ```text
+//! # everything after '#' is a comment
+//! image { width 400 aspect_ratio 16 / 9 samples 50 max_depth 50 tone_map aces }
+//! camera { look_from -2 2 1 look_at 0 0 -1 vup 0 1 0 vfov 40 shutter 0 1 }
+//! material red lambertian { albedo 1 0 0 }
+//! material lamp diffuse_light { radiance 4 4 4 }
+//! texture tiles checker { scale 0.5 even 1 1 1 odd red }
+//! material tiled lambertian { albedo tiles }
+//! sphere { center 0 0 -1 radius 0.5 material red }
+//! sphere { center 1 0 -1 center_end 1 0.5 -1 radius 0.5 material red }
+//! triangle { a 0 0 0 b 1 0 0 c 0 1 0 material red }
+//! plane { point 0 -0.5 0 normal 0 1 0 material red }
+//! disk { center 0 2 0 normal 0 -1 0 radius 0.5 material red }
+//! quad { corner -1 0 -2 u 2 0 0 v 0 1 0 material red }
+//! box { min -1 0 -3 max 0 1 -2 material red }
+//! oriented_box { center 1 0.5 -2 size 1 1 1 x_axis 1 0 1 y_axis 0 1 0 material red }
+//! cylinder { base 0 0 -2 top 0 1 -2 radius 0.3 caps true material red }
+//! cone { base 1 0 -2 apex 1 1 -2 radius 0.3 cap true material red }
+//! torus { center 0 0 -2 axis 0 1 0 major_radius 1 minor_radius 0.2 material red }
+//! light point { position 0 3 0 intensity 20 20 20 }
+//! background solid { color 0 0 0 }
+//! object blob { sphere { center 0 1 -1 radius 0.5 material red } }
+//! medium { boundary blob density 2 albedo 0.9 0.9 0.9 }
+//! mesh { file "models/teapot.obj" material red }
+//! ```
+//!
+//! Mesh files are relative to the scene file, their MTL materials are used
+//! unless a `material` is given. Texture types are `solid { color }`,
+//! `checker { scale even odd }` in space, `uv_checker { columns rows even odd }`
+//! on the surface, `image { file wrap }`, and the noise based
+//! `marble { seed scale colors }`, `wood { seed scale colors }` and
+//! `clouds { seed scale coverage colors }` where `colors` is two colors.
+//! `even`, `odd` and material albedos are colors or texture names. A sphere with a `center_end` moves from
+//! `center` at time 0 to `center_end` at time 1, it is blurred over the times
+//! the camera `shutter` is open. The background is `sky` unless set to `black`
+//! or `solid`. A medium fills the shapes of an object with smoke, their
+//! materials are ignored
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    camera::{self, Camera},
+    geometry::bvh::Bvh,
+    image::{Color, DisplayTransform, display::ToneMap},
+    loader::{
+        lexer::{self, ParseError, Token, TokenKind},
+        obj,
+    },
+    materials::{
+        dielectric::Dielectric, diffuse_light::DiffuseLight, isotropic::Isotropic,
+        lambertian::Lambertian, material::Materials, metal::Metal,
+    },
+    math::{Point3f, Vector3f, matrix4::Transform, noise::Perlin, utils},
+    scene::{Background, PointLight, Scene},
+    shapes::{
+        cone::Cone,
+        constant_medium::ConstantMedium,
+        cuboid::{Cuboid, OrientedBox},
+        cylinder::Cylinder,
+        disk::Disk,
+        mesh::Mesh,
+        plane::Plane,
+        quad::Quad,
+        shapes::Shapes,
+        sphere::Sphere,
+        torus::Torus,
+        transformed::Transformed,
+        triangle::Triangle,
+    },
+    textures::{
+        checker::{SolidChecker, UvChecker},
+        image_texture::{ImageTexture, Wrap},
+        procedural::{Clouds, Marble, Wood},
+        texture::Textures,
+    },
+};
+
+/// Material of mesh faces that have none
+const MESH_GRAY: Color<f64> = Color::new(0.8, 0.8, 0.8);
+
+/// Camera and scene described by a scene file
+pub struct SceneFile {
+    pub camera: Camera,
+    pub scene: Scene,
+    /// how the rendered image is turned into display values
+    pub display: DisplayTransform,
+}
+
+/// Read and parse the scene file at path, parse errors are reported as
+/// `InvalidData` with the path, line and column in the message
+pub fn load(path: impl AsRef<Path>) -> io::Result<SceneFile> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+    parse_in(&source, path.parent().unwrap_or(Path::new(""))).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}:{}", path.display(), e),
+        )
+    })
+}
+
+/// Parse a scene, files it references are relative to the working directory
+pub fn parse(source: &str) -> Result<SceneFile, ParseError> {
+    parse_in(source, Path::new(""))
+}
+
+/// Parse a scene whose referenced files are relative to directory
+pub fn parse_in(source: &str, directory: &Path) -> Result<SceneFile, ParseError> {
+    let mut parser = Parser {
+        tokens: lexer::tokenize(source)?,
+        directory: directory.to_path_buf(),
+        position: 0,
+        materials: HashMap::new(),
+        textures: HashMap::new(),
+        objects: HashMap::new(),
+        open_object: None,
+        image: ImageSettings::default(),
+        camera: CameraSettings::default(),
+        scene: Scene::new(),
+    };
+
+    while parser.peek().kind != TokenKind::Eof {
+        parser.statement()?;
+    }
+
+    parser.scene.build_bvh();
+
+    Ok(SceneFile {
+        camera: parser.build_camera(),
+        display: parser.image.display,
+        scene: parser.scene,
+    })
+}
+
+struct ImageSettings {
+    width: u16,
+    aspect_ratio: f32,
+    samples_per_pixel: u32,
+    max_depth: u32,
+    seed: u64,
+    display: DisplayTransform,
+}
+
+impl Default for ImageSettings {
+    fn default() -> Self {
+        Self {
+            width: 400,
+            aspect_ratio: 16.0 / 9.0,
+            samples_per_pixel: 10,
+            max_depth: 10,
+            seed: 0,
+            display: DisplayTransform::default(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct CameraSettings {
+    look_from: Option<Point3f>,
+    look_at: Option<Point3f>,
+    vup: Option<Vector3f>,
+    vfov: Option<f64>,
+    defocus_angle: Option<f64>,
+    focus_dist: Option<f64>,
+    shutter: Option<(f64, f64)>,
+}
+
+impl CameraSettings {
+    /// look_from, look_at and vup with the defaults filled in
+    fn view(&self) -> (Point3f, Point3f, Vector3f) {
+        (
+            self.look_from.unwrap_or(Vector3f::new(0.0, 0.0, 0.0)),
+            self.look_at.unwrap_or(Vector3f::new(0.0, 0.0, -1.0)),
+            self.vup.unwrap_or(Vector3f::new(0.0, 1.0, 0.0)),
+        )
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    /// directory referenced files are relative to
+    directory: PathBuf,
+    materials: HashMap<String, Materials>,
+    textures: HashMap<String, Textures>,
+    /// shapes of each object, shared by its instances
+    objects: HashMap<String, Arc<Shapes>>,
+    /// shapes of the object being defined, they go there instead of the scene
+    open_object: Option<Vec<Shapes>>,
+    image: ImageSettings,
+    camera: CameraSettings,
+    scene: Scene,
+}
+
+/// Value of a required property, missing ones are reported at the block
+fn required<T>(value: Option<T>, block: &Token, name: &str) -> Result<T, ParseError> {
+    value.ok_or_else(|| ParseError::at(block, format!("missing property '{name}'")))
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position]
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.position].clone();
+        // the last token is always Eof, stay on it
+        if self.position + 1 < self.tokens.len() {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<Token, ParseError> {
+        let token = self.next();
+        if token.kind == kind {
+            Ok(token)
+        } else {
+            Err(ParseError::at(
+                &token,
+                format!("expected {kind}, found {}", token.kind),
+            ))
+        }
+    }
+
+    fn ident(&mut self) -> Result<(String, Token), ParseError> {
+        let token = self.next();
+        match &token.kind {
+            TokenKind::Ident(name) => Ok((name.clone(), token)),
+            kind => Err(ParseError::at(
+                &token,
+                format!("expected a name, found {kind}"),
+            )),
+        }
+    }
+
+    /// a number, or a ratio written as `a / b`
+    fn number(&mut self) -> Result<f64, ParseError> {
+        let token = self.next();
+        let TokenKind::Number(n) = token.kind else {
+            return Err(ParseError::at(
+                &token,
+                format!("expected a number, found {}", token.kind),
+            ));
+        };
+
+        if self.peek().kind == TokenKind::Slash {
+            self.next();
+            let divisor_token = self.peek().clone();
+            let divisor = self.number()?;
+            if divisor == 0.0 {
+                return Err(ParseError::at(&divisor_token, "division by zero"));
+            }
+            return Ok(n / divisor);
+        }
+
+        Ok(n)
+    }
+
+    /// a whole number no larger than max
+    fn unsigned(&mut self, max: u64) -> Result<u64, ParseError> {
+        let token = self.peek().clone();
+        let n = self.number()?;
+        if n < 0.0 || n.fract() != 0.0 || n > max as f64 {
+            return Err(ParseError::at(
+                &token,
+                format!("expected a whole number between 0 and {max}"),
+            ));
+        }
+        Ok(n as u64)
+    }
+
+    /// `true` or `false`
+    fn boolean(&mut self) -> Result<bool, ParseError> {
+        let (word, token) = self.ident()?;
+        match word.as_str() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            _ => Err(ParseError::at(
+                &token,
+                format!("expected true or false, found '{word}'"),
+            )),
+        }
+    }
+
+    fn vector(&mut self) -> Result<Vector3f, ParseError> {
+        Ok(Vector3f::new(
+            self.number()?,
+            self.number()?,
+            self.number()?,
+        ))
+    }
+
+    /// texture coordinate pair
+    fn uv(&mut self) -> Result<(f64, f64), ParseError> {
+        Ok((self.number()?, self.number()?))
+    }
+
+    fn color(&mut self) -> Result<Color<f64>, ParseError> {
+        Ok(Color::new(self.number()?, self.number()?, self.number()?))
+    }
+
+    /// Quoted string, returned with its token for error positions
+    fn string(&mut self) -> Result<(String, Token), ParseError> {
+        let token = self.next();
+        match &token.kind {
+            TokenKind::Str(text) => Ok((text.clone(), token.clone())),
+            kind => Err(ParseError::at(
+                &token,
+                format!("expected a string, found {kind}"),
+            )),
+        }
+    }
+
+    /// reference to a material declared earlier in the file
+    fn material(&mut self) -> Result<Materials, ParseError> {
+        let (name, token) = self.ident()?;
+        self.materials
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| ParseError::at(&token, format!("undefined material '{name}'")))
+    }
+
+    /// a color, or the name of a texture declared earlier in the file
+    fn texture(&mut self) -> Result<Textures, ParseError> {
+        if !matches!(self.peek().kind, TokenKind::Ident(_)) {
+            return Ok(self.color()?.into());
+        }
+
+        let (name, token) = self.ident()?;
+        self.textures
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| ParseError::at(&token, format!("undefined texture '{name}'")))
+    }
+
+    /// Parse `{ key value... }`, calling property for each key, returns the
+    /// opening brace so missing properties can point at the block
+    fn block(
+        &mut self,
+        what: &str,
+        mut property: impl FnMut(&mut Self, &str) -> Result<bool, ParseError>,
+    ) -> Result<Token, ParseError> {
+        let open = self.expect(TokenKind::LBrace)?;
+
+        loop {
+            if self.peek().kind == TokenKind::RBrace {
+                self.next();
+                return Ok(open);
+            }
+
+            let (key, token) = self.ident()?;
+            if !property(self, &key)? {
+                return Err(ParseError::at(
+                    &token,
+                    format!("unknown property '{key}' in {what}"),
+                ));
+            }
+        }
+    }
+
+    fn add_shape(&mut self, shape: Shapes) {
+        match &mut self.open_object {
+            Some(shapes) => shapes.push(shape),
+            None => self.scene.add(shape),
+        }
+    }
+
+    fn statement(&mut self) -> Result<(), ParseError> {
+        let (keyword, token) = self.ident()?;
+
+        if self.open_object.is_some()
+            && matches!(
+                keyword.as_str(),
+                "image" | "camera" | "light" | "background" | "object"
+            )
+        {
+            return Err(ParseError::at(
+                &token,
+                format!("'{keyword}' is not allowed inside an object"),
+            ));
+        }
+
+        match keyword.as_str() {
+            "image" => self.image_block(),
+            "camera" => self.camera_block(),
+            "background" => self.background_statement(),
+            "material" => self.material_statement(),
+            "texture" => self.texture_statement(),
+            "sphere" => self.sphere_block(),
+            "triangle" => self.triangle_block(),
+            "mesh" => self.mesh_block(),
+            "plane" => self.plane_block(),
+            "disk" => self.disk_block(),
+            "quad" => self.quad_block(),
+            "box" => self.box_block(),
+            "oriented_box" => self.oriented_box_block(),
+            "cylinder" => self.cylinder_block(),
+            "cone" => self.cone_block(),
+            "torus" => self.torus_block(),
+            "light" => self.light_statement(),
+            "object" => self.object_statement(),
+            "instance" => self.instance_statement(),
+            "medium" => self.medium_block(),
+            _ => Err(ParseError::at(
+                &token,
+                format!("unknown statement '{keyword}'"),
+            )),
+        }
+    }
+
+    fn image_block(&mut self) -> Result<(), ParseError> {
+        let mut white = None;
+        self.block("image", |p, key| {
+            match key {
+                "width" => {
+                    let token = p.peek().clone();
+                    let width = p.unsigned(u64::from(u16::MAX))? as u16;
+                    if width == 0 {
+                        return Err(ParseError::at(&token, "width must be at least 1"));
+                    }
+                    p.image.width = width;
+                }
+                "aspect_ratio" => {
+                    let token = p.peek().clone();
+                    let ratio = p.number()? as f32;
+                    if !ratio.is_finite() || ratio <= 0.0 {
+                        return Err(ParseError::at(&token, "aspect ratio must be positive"));
+                    }
+                    p.image.aspect_ratio = ratio;
+                }
+                "samples" => p.image.samples_per_pixel = p.unsigned(u64::from(u32::MAX))? as u32,
+                "max_depth" => p.image.max_depth = p.unsigned(u64::from(u32::MAX))? as u32,
+                "seed" => p.image.seed = p.unsigned(u64::MAX)?,
+                "exposure" => p.image.display.exposure = p.number()?,
+                "srgb" => p.image.display.srgb = p.boolean()?,
+                "white" => {
+                    let token = p.peek().clone();
+                    let w = p.number()?;
+                    if w <= 0.0 || w.is_nan() {
+                        return Err(ParseError::at(&token, "white point must be positive"));
+                    }
+                    white = Some((w, token));
+                }
+                "tone_map" => {
+                    let (name, token) = p.ident()?;
+                    p.image.display.tone_map = match name.as_str() {
+                        "clamp" => ToneMap::Clamp,
+                        "reinhard" => ToneMap::Reinhard,
+                        "extended_reinhard" => ToneMap::ExtendedReinhard { white: 1.0 },
+                        "aces" => ToneMap::Aces,
+                        _ => {
+                            return Err(ParseError::at(
+                                &token,
+                                format!("unknown tone map '{name}'"),
+                            ));
+                        }
+                    };
+                }
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        // the white point can be given before or after the tone map
+        if let Some((w, token)) = white {
+            match &mut self.image.display.tone_map {
+                ToneMap::ExtendedReinhard { white } => *white = w,
+                _ => {
+                    return Err(ParseError::at(
+                        &token,
+                        "white only applies to the extended_reinhard tone map",
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn camera_block(&mut self) -> Result<(), ParseError> {
+        let block = self.block("camera", |p, key| {
+            match key {
+                "look_from" => p.camera.look_from = Some(p.vector()?),
+                "look_at" => p.camera.look_at = Some(p.vector()?),
+                "vup" => p.camera.vup = Some(p.vector()?),
+                "vfov" => {
+                    let token = p.peek().clone();
+                    let vfov = p.number()?;
+                    if !(vfov > 0.0 && vfov < 180.0) {
+                        return Err(ParseError::at(
+                            &token,
+                            "vfov must be between 0 and 180 degrees",
+                        ));
+                    }
+                    p.camera.vfov = Some(vfov);
+                }
+                "defocus_angle" => p.camera.defocus_angle = Some(p.number()?),
+                "focus_dist" => p.camera.focus_dist = Some(p.number()?),
+                "shutter" => {
+                    let open = p.number()?;
+                    let token = p.peek().clone();
+                    let close = p.number()?;
+                    if close < open {
+                        return Err(ParseError::at(&token, "shutter closes before it opens"));
+                    }
+                    p.camera.shutter = Some((open, close));
+                }
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        let (look_from, look_at, vup) = self.camera.view();
+        if look_from == look_at {
+            return Err(ParseError::at(
+                &block,
+                "look_from and look_at are the same point",
+            ));
+        }
+        if camera::is_degenerate_view(&look_from, &look_at, &vup) {
+            return Err(ParseError::at(
+                &block,
+                "vup must not be parallel to the view direction",
+            ));
+        }
+        Ok(())
+    }
+
+    /// `material <name> <type> { ... }`
+    fn material_statement(&mut self) -> Result<(), ParseError> {
+        let (name, name_token) = self.ident()?;
+        if self.materials.contains_key(&name) {
+            return Err(ParseError::at(
+                &name_token,
+                format!("material '{name}' is already defined"),
+            ));
+        }
+
+        let (kind, kind_token) = self.ident()?;
+        let material = match kind.as_str() {
+            "lambertian" => {
+                let mut albedo = None;
+                let block = self.block("lambertian material", |p, key| {
+                    match key {
+                        "albedo" => albedo = Some(p.texture()?),
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+                Materials::Lambertian(Lambertian::textured(required(albedo, &block, "albedo")?))
+            }
+            "metal" => {
+                let mut albedo = None;
+                let mut fuzz = 0.0;
+                let block = self.block("metal material", |p, key| {
+                    match key {
+                        "albedo" => albedo = Some(p.texture()?),
+                        "fuzz" => fuzz = p.number()?,
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+                Materials::Metal(Metal::textured(required(albedo, &block, "albedo")?, fuzz))
+            }
+            "dielectric" => {
+                let mut ior = None;
+                let block = self.block("dielectric material", |p, key| {
+                    match key {
+                        "ior" => ior = Some(p.number()?),
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+                Materials::Dielectric(Dielectric::new(required(ior, &block, "ior")?))
+            }
+            "diffuse_light" => {
+                let mut radiance = None;
+                let block = self.block("diffuse_light material", |p, key| {
+                    match key {
+                        "radiance" => radiance = Some(p.texture()?),
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+                Materials::DiffuseLight(DiffuseLight::textured(required(
+                    radiance, &block, "radiance",
+                )?))
+            }
+            _ => {
+                return Err(ParseError::at(
+                    &kind_token,
+                    format!("unknown material type '{kind}'"),
+                ));
+            }
+        };
+
+        self.materials.insert(name, material);
+        Ok(())
+    }
+
+    /// `texture <name> <type> { ... }`
+    fn texture_statement(&mut self) -> Result<(), ParseError> {
+        let (name, name_token) = self.ident()?;
+        if self.textures.contains_key(&name) {
+            return Err(ParseError::at(
+                &name_token,
+                format!("texture '{name}' is already defined"),
+            ));
+        }
+
+        let (kind, kind_token) = self.ident()?;
+        let texture = match kind.as_str() {
+            "solid" => {
+                let mut color = None;
+                let block = self.block("solid texture", |p, key| {
+                    match key {
+                        "color" => color = Some(p.color()?),
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+                required(color, &block, "color")?.into()
+            }
+            "checker" => {
+                let mut scale = 1.0;
+                let mut even = None;
+                let mut odd = None;
+                let block = self.block("checker texture", |p, key| {
+                    match key {
+                        "scale" => scale = p.number()?,
+                        "even" => even = Some(p.texture()?),
+                        "odd" => odd = Some(p.texture()?),
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+                Textures::SolidChecker(SolidChecker::new(
+                    scale,
+                    required(even, &block, "even")?,
+                    required(odd, &block, "odd")?,
+                ))
+            }
+            "uv_checker" => {
+                let mut columns = 8.0;
+                let mut rows = 8.0;
+                let mut even = None;
+                let mut odd = None;
+                let block = self.block("uv_checker texture", |p, key| {
+                    match key {
+                        "columns" => columns = p.number()?,
+                        "rows" => rows = p.number()?,
+                        "even" => even = Some(p.texture()?),
+                        "odd" => odd = Some(p.texture()?),
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+                Textures::UvChecker(UvChecker::new(
+                    columns,
+                    rows,
+                    required(even, &block, "even")?,
+                    required(odd, &block, "odd")?,
+                ))
+            }
+            "image" => {
+                let mut file = None;
+                let mut wrap = Wrap::default();
+                let block = self.block("image texture", |p, key| {
+                    match key {
+                        "file" => file = Some(p.string()?),
+                        "wrap" => {
+                            let (mode, token) = p.ident()?;
+                            wrap = match mode.as_str() {
+                                "repeat" => Wrap::Repeat,
+                                "clamp" => Wrap::Clamp,
+                                "mirror" => Wrap::Mirror,
+                                _ => {
+                                    return Err(ParseError::at(
+                                        &token,
+                                        format!("unknown wrap mode '{mode}'"),
+                                    ));
+                                }
+                            };
+                        }
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+
+                let (file, file_token) = required(file, &block, "file")?;
+                let texture = ImageTexture::load(self.directory.join(file))
+                    .map_err(|e| ParseError::at(&file_token, e.to_string()))?;
+                Textures::Image(texture.with_wrap(wrap))
+            }
+            "marble" | "wood" => {
+                let mut seed = 0;
+                let mut scale = 1.0;
+                let mut colors = None;
+                self.block(&format!("{kind} texture"), |p, key| {
+                    match key {
+                        "seed" => seed = p.unsigned(u64::MAX)?,
+                        "scale" => scale = p.number()?,
+                        "colors" => colors = Some((p.color()?, p.color()?)),
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+
+                let noise = Arc::new(Perlin::new(seed));
+                if kind == "marble" {
+                    let mut marble = Marble::new(noise, scale);
+                    if let Some((base, vein)) = colors {
+                        marble = marble.with_colors(base, vein);
+                    }
+                    Textures::Marble(marble)
+                } else {
+                    let mut wood = Wood::new(noise, scale);
+                    if let Some((light, dark)) = colors {
+                        wood = wood.with_colors(light, dark);
+                    }
+                    Textures::Wood(wood)
+                }
+            }
+            "clouds" => {
+                let mut seed = 0;
+                let mut scale = 1.0;
+                let mut coverage = None;
+                let mut colors = None;
+                self.block("clouds texture", |p, key| {
+                    match key {
+                        "seed" => seed = p.unsigned(u64::MAX)?,
+                        "scale" => scale = p.number()?,
+                        "coverage" => coverage = Some(p.number()?),
+                        "colors" => colors = Some((p.color()?, p.color()?)),
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+
+                let mut clouds = Clouds::new(Arc::new(Perlin::new(seed)), scale);
+                if let Some(coverage) = coverage {
+                    clouds = clouds.with_coverage(coverage);
+                }
+                if let Some((sky, cloud)) = colors {
+                    clouds = clouds.with_colors(sky, cloud);
+                }
+                Textures::Clouds(clouds)
+            }
+            _ => {
+                return Err(ParseError::at(
+                    &kind_token,
+                    format!("unknown texture type '{kind}'"),
+                ));
+            }
+        };
+
+        self.textures.insert(name, texture);
+        Ok(())
+    }
+
+    fn sphere_block(&mut self) -> Result<(), ParseError> {
+        let mut center = None;
+        let mut center_end = None;
+        let mut radius = None;
+        let mut material = None;
+        let block = self.block("sphere", |p, key| {
+            match key {
+                "center" => center = Some(p.vector()?),
+                "center_end" => center_end = Some(p.vector()?),
+                "radius" => radius = Some(p.number()?),
+                "material" => material = Some(p.material()?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        let radius = required(radius, &block, "radius")?;
+        let center = required(center, &block, "center")?;
+        let material = required(material, &block, "material")?;
+        let sphere = match center_end {
+            Some(end) => Sphere::moving(radius, center, end, material),
+            None => Sphere::new(radius, center, material),
+        };
+        self.add_shape(Shapes::Sphere(sphere));
+        Ok(())
+    }
+
+    /// Vertices a, b and c, with optional normals (na, nb, nc) and texture
+    /// coordinates (uva, uvb, uvc) that must be given for all three vertices
+    fn triangle_block(&mut self) -> Result<(), ParseError> {
+        let mut vertices = [None; 3];
+        let mut normals = [None; 3];
+        let mut uvs = [None; 3];
+        let mut material = None;
+        let block = self.block("triangle", |p, key| {
+            match key {
+                "a" => vertices[0] = Some(p.vector()?),
+                "b" => vertices[1] = Some(p.vector()?),
+                "c" => vertices[2] = Some(p.vector()?),
+                "na" => normals[0] = Some(p.vector()?),
+                "nb" => normals[1] = Some(p.vector()?),
+                "nc" => normals[2] = Some(p.vector()?),
+                "uva" => uvs[0] = Some(p.uv()?),
+                "uvb" => uvs[1] = Some(p.uv()?),
+                "uvc" => uvs[2] = Some(p.uv()?),
+                "material" => material = Some(p.material()?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        let mut triangle = Triangle::new(
+            required(vertices[0], &block, "a")?,
+            required(vertices[1], &block, "b")?,
+            required(vertices[2], &block, "c")?,
+            required(material, &block, "material")?,
+        );
+
+        if normals.iter().any(Option::is_some) {
+            triangle = triangle.with_normals([
+                required(normals[0], &block, "na")?,
+                required(normals[1], &block, "nb")?,
+                required(normals[2], &block, "nc")?,
+            ]);
+        }
+        if uvs.iter().any(Option::is_some) {
+            triangle = triangle.with_uvs([
+                required(uvs[0], &block, "uva")?,
+                required(uvs[1], &block, "uvb")?,
+                required(uvs[2], &block, "uvc")?,
+            ]);
+        }
+
+        self.add_shape(Shapes::Triangle(triangle));
+        Ok(())
+    }
+
+    fn plane_block(&mut self) -> Result<(), ParseError> {
+        let mut point = None;
+        let mut normal = None;
+        let mut material = None;
+        let block = self.block("plane", |p, key| {
+            match key {
+                "point" => point = Some(p.vector()?),
+                "normal" => normal = Some(p.vector()?),
+                "material" => material = Some(p.material()?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        self.add_shape(Shapes::Plane(Plane::new(
+            required(point, &block, "point")?,
+            required(normal, &block, "normal")?,
+            required(material, &block, "material")?,
+        )));
+        Ok(())
+    }
+
+    fn disk_block(&mut self) -> Result<(), ParseError> {
+        let mut center = None;
+        let mut normal = None;
+        let mut radius = None;
+        let mut material = None;
+        let block = self.block("disk", |p, key| {
+            match key {
+                "center" => center = Some(p.vector()?),
+                "normal" => normal = Some(p.vector()?),
+                "radius" => radius = Some(p.number()?),
+                "material" => material = Some(p.material()?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        self.add_shape(Shapes::Disk(Disk::new(
+            required(center, &block, "center")?,
+            required(normal, &block, "normal")?,
+            required(radius, &block, "radius")?,
+            required(material, &block, "material")?,
+        )));
+        Ok(())
+    }
+
+    /// Parallelogram spanned by the edges u and v from corner
+    fn quad_block(&mut self) -> Result<(), ParseError> {
+        let mut corner = None;
+        let mut u = None;
+        let mut v = None;
+        let mut material = None;
+        let block = self.block("quad", |p, key| {
+            match key {
+                "corner" => corner = Some(p.vector()?),
+                "u" => u = Some(p.vector()?),
+                "v" => v = Some(p.vector()?),
+                "material" => material = Some(p.material()?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        self.add_shape(Shapes::Quad(Quad::new(
+            required(corner, &block, "corner")?,
+            required(u, &block, "u")?,
+            required(v, &block, "v")?,
+            required(material, &block, "material")?,
+        )));
+        Ok(())
+    }
+
+    /// Axis aligned box between the corners min and max
+    fn box_block(&mut self) -> Result<(), ParseError> {
+        let mut min = None;
+        let mut max = None;
+        let mut material = None;
+        let block = self.block("box", |p, key| {
+            match key {
+                "min" => min = Some(p.vector()?),
+                "max" => max = Some(p.vector()?),
+                "material" => material = Some(p.material()?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        self.add_shape(Shapes::Cuboid(Cuboid::new(
+            required(min, &block, "min")?,
+            required(max, &block, "max")?,
+            required(material, &block, "material")?,
+        )));
+        Ok(())
+    }
+
+    /// Box of the given size along its local axes, which default to the world axes
+    fn oriented_box_block(&mut self) -> Result<(), ParseError> {
+        let mut center = None;
+        let mut size = None;
+        let mut x_axis = Vector3f::new(1.0, 0.0, 0.0);
+        let mut y_axis = Vector3f::new(0.0, 1.0, 0.0);
+        let mut material = None;
+        let block = self.block("oriented box", |p, key| {
+            match key {
+                "center" => center = Some(p.vector()?),
+                "size" => size = Some(p.vector()?),
+                "x_axis" => x_axis = p.vector()?,
+                "y_axis" => y_axis = p.vector()?,
+                "material" => material = Some(p.material()?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        if x_axis.cross(&y_axis).norm_squared() < 1e-12 {
+            return Err(ParseError::at(
+                &block,
+                "x_axis and y_axis must not be parallel",
+            ));
+        }
+
+        self.add_shape(Shapes::OrientedBox(OrientedBox::new(
+            required(center, &block, "center")?,
+            required(size, &block, "size")?,
+            x_axis,
+            y_axis,
+            required(material, &block, "material")?,
+        )));
+        Ok(())
+    }
+
+    /// Cylinder from base to top, open unless caps is true
+    fn cylinder_block(&mut self) -> Result<(), ParseError> {
+        let mut base = None;
+        let mut top = None;
+        let mut radius = None;
+        let mut caps = false;
+        let mut material = None;
+        let block = self.block("cylinder", |p, key| {
+            match key {
+                "base" => base = Some(p.vector()?),
+                "top" => top = Some(p.vector()?),
+                "radius" => radius = Some(p.number()?),
+                "caps" => caps = p.boolean()?,
+                "material" => material = Some(p.material()?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        let mut cylinder = Cylinder::new(
+            required(base, &block, "base")?,
+            required(top, &block, "top")?,
+            required(radius, &block, "radius")?,
+            required(material, &block, "material")?,
+        );
+        if caps {
+            cylinder = cylinder.with_caps();
+        }
+        self.add_shape(Shapes::Cylinder(cylinder));
+        Ok(())
+    }
+
+    /// Cone from a base circle to apex, open unless cap is true
+    fn cone_block(&mut self) -> Result<(), ParseError> {
+        let mut base = None;
+        let mut apex = None;
+        let mut radius = None;
+        let mut cap = false;
+        let mut material = None;
+        let block = self.block("cone", |p, key| {
+            match key {
+                "base" => base = Some(p.vector()?),
+                "apex" => apex = Some(p.vector()?),
+                "radius" => radius = Some(p.number()?),
+                "cap" => cap = p.boolean()?,
+                "material" => material = Some(p.material()?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        let mut cone = Cone::new(
+            required(base, &block, "base")?,
+            required(apex, &block, "apex")?,
+            required(radius, &block, "radius")?,
+            required(material, &block, "material")?,
+        );
+        if cap {
+            cone = cone.with_cap();
+        }
+        self.add_shape(Shapes::Cone(cone));
+        Ok(())
+    }
+
+    fn torus_block(&mut self) -> Result<(), ParseError> {
+        let mut center = None;
+        let mut axis = None;
+        let mut major_radius = None;
+        let mut minor_radius = None;
+        let mut material = None;
+        let block = self.block("torus", |p, key| {
+            match key {
+                "center" => center = Some(p.vector()?),
+                "axis" => axis = Some(p.vector()?),
+                "major_radius" => major_radius = Some(p.number()?),
+                "minor_radius" => minor_radius = Some(p.number()?),
+                "material" => material = Some(p.material()?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        self.add_shape(Shapes::Torus(Torus::new(
+            required(center, &block, "center")?,
+            required(axis, &block, "axis")?,
+            required(major_radius, &block, "major_radius")?,
+            required(minor_radius, &block, "minor_radius")?,
+            required(material, &block, "material")?,
+        )));
+        Ok(())
+    }
+
+    /// OBJ model, with all faces using material when one is given
+    fn mesh_block(&mut self) -> Result<(), ParseError> {
+        let mut file = None;
+        let mut material = None;
+        let block = self.block("mesh", |p, key| {
+            match key {
+                "file" => file = Some(p.string()?),
+                "material" => material = Some(p.material()?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        let (file, file_token) = required(file, &block, "file")?;
+        let path = self.directory.join(file);
+        let mut data = obj::load(&path, &Materials::Lambertian(Lambertian::new(MESH_GRAY)))
+            .map_err(|e| ParseError::at(&file_token, e.to_string()))?;
+        if let Some(material) = material {
+            data.replace_materials(material);
+        }
+
+        self.add_shape(Shapes::Mesh(Mesh::new(data)));
+        Ok(())
+    }
+
+    /// `object <name> { <shape statements> }`
+    fn object_statement(&mut self) -> Result<(), ParseError> {
+        let (name, name_token) = self.ident()?;
+        if self.objects.contains_key(&name) {
+            return Err(ParseError::at(
+                &name_token,
+                format!("object '{name}' is already defined"),
+            ));
+        }
+
+        self.expect(TokenKind::LBrace)?;
+        self.open_object = Some(Vec::new());
+        while self.peek().kind != TokenKind::RBrace {
+            if self.peek().kind == TokenKind::Eof {
+                let token = self.next();
+                return Err(ParseError::at(
+                    &token,
+                    format!("expected '}}', found {}", token.kind),
+                ));
+            }
+            self.statement()?;
+        }
+        self.next();
+
+        let mut shapes = self.open_object.take().unwrap_or_default();
+        let object = if shapes.len() == 1 {
+            shapes.remove(0)
+        } else {
+            Shapes::Bvh(Bvh::new(shapes))
+        };
+        self.objects.insert(name, Arc::new(object));
+        Ok(())
+    }
+
+    /// `instance <object> { translate x y z | rotate x y z degrees | scale x y z ... }`
+    fn instance_statement(&mut self) -> Result<(), ParseError> {
+        let (name, name_token) = self.ident()?;
+        let object = self
+            .objects
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| ParseError::at(&name_token, format!("undefined object '{name}'")))?;
+
+        let mut transform = Transform::default();
+        self.block("instance", |p, key| {
+            let token = p.peek().clone();
+            let next = match key {
+                "translate" => Transform::translation(p.vector()?),
+                "rotate" => {
+                    let axis = p.vector()?;
+                    if utils::near_zero_vector(&axis) {
+                        return Err(ParseError::at(&token, "rotation axis must not be zero"));
+                    }
+                    Transform::rotation(axis, p.number()?)
+                }
+                "scale" => {
+                    let factors = p.vector()?;
+                    if factors.x == 0.0 || factors.y == 0.0 || factors.z == 0.0 {
+                        return Err(ParseError::at(&token, "scale factors must not be zero"));
+                    }
+                    Transform::scaling(factors)
+                }
+                _ => return Ok(false),
+            };
+            transform = transform.then(&next);
+            Ok(true)
+        })?;
+
+        self.add_shape(Shapes::Transformed(Transformed::new(object, transform)));
+        Ok(())
+    }
+
+    /// `light <type> { ... }`
+    fn light_statement(&mut self) -> Result<(), ParseError> {
+        let (kind, kind_token) = self.ident()?;
+        match kind.as_str() {
+            "point" => {
+                let mut position = None;
+                let mut intensity = None;
+                let block = self.block("point light", |p, key| {
+                    match key {
+                        "position" => position = Some(p.vector()?),
+                        "intensity" => intensity = Some(p.color()?),
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+
+                self.scene.add_light(PointLight::new(
+                    required(position, &block, "position")?,
+                    required(intensity, &block, "intensity")?,
+                ));
+                Ok(())
+            }
+            _ => Err(ParseError::at(
+                &kind_token,
+                format!("unknown light type '{kind}'"),
+            )),
+        }
+    }
+
+    /// volume filling the shapes of an object
+    fn medium_block(&mut self) -> Result<(), ParseError> {
+        let mut boundary = None;
+        let mut density = None;
+        let mut albedo = None;
+        let block = self.block("medium", |p, key| {
+            match key {
+                "boundary" => {
+                    let (name, token) = p.ident()?;
+                    let object = p.objects.get(&name).cloned().ok_or_else(|| {
+                        ParseError::at(&token, format!("undefined object '{name}'"))
+                    })?;
+                    boundary = Some(object);
+                }
+                "density" => {
+                    let token = p.peek().clone();
+                    let d = p.number()?;
+                    if d <= 0.0 {
+                        return Err(ParseError::at(&token, "density must be positive"));
+                    }
+                    density = Some(d);
+                }
+                "albedo" => albedo = Some(p.texture()?),
+                _ => return Ok(false),
+            }
+            Ok(true)
+        })?;
+
+        self.add_shape(Shapes::ConstantMedium(ConstantMedium::new(
+            required(boundary, &block, "boundary")?,
+            required(density, &block, "density")?,
+            Materials::Isotropic(Isotropic::textured(required(albedo, &block, "albedo")?)),
+        )));
+        Ok(())
+    }
+
+    /// `background sky`, `background black` or `background solid { color }`
+    fn background_statement(&mut self) -> Result<(), ParseError> {
+        let (kind, kind_token) = self.ident()?;
+        let background = match kind.as_str() {
+            "sky" => Background::Sky,
+            "black" => Background::Solid(Color::new(0.0, 0.0, 0.0)),
+            "solid" => {
+                let mut color = None;
+                let block = self.block("solid background", |p, key| {
+                    match key {
+                        "color" => color = Some(p.color()?),
+                        _ => return Ok(false),
+                    }
+                    Ok(true)
+                })?;
+                Background::Solid(required(color, &block, "color")?)
+            }
+            _ => {
+                return Err(ParseError::at(
+                    &kind_token,
+                    format!("unknown background '{kind}'"),
+                ));
+            }
+        };
+
+        self.scene.set_background(background);
+        Ok(())
+    }
+
+    fn build_camera(&self) -> Camera {
+        let image = &self.image;
+        let settings = &self.camera;
+
+        let (look_from, look_at, vup) = settings.view();
+
+        let mut camera = Camera::new(image.aspect_ratio, image.width)
+            .with_samples_per_pixel(image.samples_per_pixel)
+            .with_max_depth(image.max_depth)
+            .with_seed(image.seed)
+            .with_view(look_from, look_at, vup);
+
+        if let Some(vfov) = settings.vfov {
+            camera = camera.with_vfov(vfov);
+        }
+
+        if settings.defocus_angle.is_some() || settings.focus_dist.is_some() {
+            camera = camera.with_defocus(
+                settings.defocus_angle.unwrap_or(0.0),
+                settings
+                    .focus_dist
+                    .unwrap_or_else(|| (look_from - look_at).norm()),
+            );
+        }
+
+        if let Some((open, close)) = settings.shutter {
+            camera = camera.with_shutter(open, close);
+        }
+
+        camera
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geometry::intersectable::Intersectable, interval::Interval, materials::material::Material,
+        math::Ray,
+    };
+
+    fn parse_error(source: &str) -> ParseError {
+        match parse(source) {
+            Ok(_) => panic!("expected a parse error"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn full_scene() {
+        let file = parse(
+            "image { width 200 aspect_ratio 16 / 9 samples 4 max_depth 8
+                     tone_map extended_reinhard white 4 exposure -1 }
+             camera { look_from 0 0 1 look_at 0 0 -1 vfov 60 defocus_angle 1 }
+             material red lambertian { albedo 1 0 0 }
+             material glass dielectric { ior 1.5 }
+             material gold metal { albedo 0.8 0.6 0.2 fuzz 0.1 }
+             sphere { center 0 0 -1 radius 0.5 material red }
+             sphere { center 1 0 -1 radius 0.5 material glass }
+             triangle { a 0 0 -2 b 1 0 -2 c 0 1 -2 uva 0 0 uvb 1 0 uvc 0 1 material gold }
+             plane { point 0 -0.5 0 normal 0 1 0 material red }
+             disk { center 0 2 0 normal 0 -1 0 radius 0.5 material red }
+             quad { corner -1 0 -3 u 2 0 0 v 0 1 0 material gold }
+             box { min -1 0 -3 max 0 1 -2 material red }
+             oriented_box { center 1 0.5 -2 size 1 1 1 x_axis 1 0 1 material red }
+             cylinder { base 0 0 -4 top 0 1 -4 radius 0.3 caps true material red }
+             cone { base 1 0 -4 apex 1 1 -4 radius 0.3 cap true material red }
+             torus { center 0 0 -5 axis 0 1 0 major_radius 1 minor_radius 0.2 material red }
+             light point { position 0 3 0 intensity 10 10 10 }",
+        )
+        .unwrap();
+
+        assert_eq!(file.scene.lights().len(), 1);
+        assert_eq!(
+            file.display.tone_map,
+            ToneMap::ExtendedReinhard { white: 4.0 }
+        );
+        assert_eq!(file.display.exposure, -1.0);
+        assert!(!file.scene.objects().is_empty());
+    }
+
+    #[test]
+    fn moving_sphere() {
+        let file = parse(
+            "camera { shutter 0 1 }
+             material red lambertian { albedo 1 0 0 }
+             sphere { center 0 0 -3 center_end 2 0 -3 radius 0.5 material red }",
+        )
+        .unwrap();
+
+        let ray = Ray::new(Vector3f::new(2.0, 0.0, 0.0), Vector3f::new(0.0, 0.0, -1.0));
+        let interval = Interval::new(0.0, f64::INFINITY);
+        assert!(file.scene.intersect(&ray, interval).is_none());
+        assert!(
+            file.scene
+                .intersect(&ray.with_time(1.0), interval)
+                .is_some()
+        );
+
+        let err = parse_error("camera { shutter 1 0.5 }");
+        assert_eq!((err.line, err.column), (1, 20));
+    }
+
+    #[test]
+    fn textures() {
+        let file = parse(
+            "texture white solid { color 1 1 1 }
+             texture tiles checker { scale 1 even white odd 0 0 0 }
+             texture squares uv_checker { columns 4 rows 2 even tiles odd 1 0 0 }
+             texture stone marble { seed 3 scale 4 colors 1 1 1 0.1 0.1 0.1 }
+             texture oak wood { seed 4 }
+             texture sky clouds { seed 5 scale 2 coverage 0.3 }
+             material tiled lambertian { albedo squares }
+             material shiny metal { albedo tiles fuzz 0 }
+             plane { point 0 0 0 normal 0 1 0 material tiled }",
+        )
+        .unwrap();
+        assert!(!file.scene.objects().is_empty());
+
+        let err = parse_error("material a lambertian { albedo missing }");
+        assert!(err.message.contains("undefined texture 'missing'"));
+        let err = parse_error("texture a image { file \"missing.ppm\" }");
+        assert_eq!((err.line, err.column), (1, 24));
+        let err = parse_error("texture a image { file \"a.ppm\" wrap around }");
+        assert!(err.message.contains("unknown wrap mode"));
+    }
+
+    #[test]
+    fn lights_and_background() {
+        let file = parse(
+            "background black
+             material lamp diffuse_light { radiance 4 4 4 }
+             quad { corner -1 2 -1 u 2 0 0 v 0 0 2 material lamp }",
+        )
+        .unwrap();
+        assert_eq!(
+            file.scene.background(),
+            Background::Solid(Color::new(0.0, 0.0, 0.0))
+        );
+
+        let ray = Ray::new(Vector3f::new(0.0, 0.0, 0.0), Vector3f::new(0.0, 1.0, 0.0));
+        let hit = file
+            .scene
+            .intersect(&ray, Interval::new(0.0, f64::INFINITY))
+            .unwrap();
+        assert_eq!(hit.material.emitted(&hit), Color::new(4.0, 4.0, 4.0));
+
+        let file = parse("background solid { color 0.1 0.2 0.3 }").unwrap();
+        assert_eq!(
+            file.scene.background(),
+            Background::Solid(Color::new(0.1, 0.2, 0.3))
+        );
+        assert_eq!(parse("").unwrap().scene.background(), Background::Sky);
+
+        let err = parse_error("background grey");
+        assert!(err.message.contains("unknown background"));
+    }
+
+    #[test]
+    fn medium() {
+        let file = parse(
+            "material red lambertian { albedo 1 0 0 }
+             object blob { sphere { center 0 0 -3 radius 1 material red } }
+             medium { boundary blob density 1000000 albedo 0.5 0.5 0.5 }",
+        )
+        .unwrap();
+
+        let ray = Ray::new(Vector3f::new(0.0, 0.0, 0.0), Vector3f::new(0.0, 0.0, -1.0));
+        let hit = file
+            .scene
+            .intersect(&ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        assert!(matches!(hit.material, Materials::Isotropic(_)));
+        assert!((hit.time - 2.0).abs() < 1e-3);
+
+        let err = parse_error("medium { boundary fog density 1 albedo 1 1 1 }");
+        assert!(err.message.contains("undefined object 'fog'"));
+        let err = parse_error("medium { density 0 }");
+        assert_eq!((err.line, err.column), (1, 18));
+    }
+
+    #[test]
+    fn unknown_property() {
+        let err = parse_error("sphere {\n  centre 0 0 0\n}");
+        assert_eq!((err.line, err.column), (2, 3));
+        assert!(err.message.contains("centre"));
+    }
+
+    #[test]
+    fn undefined_material() {
+        let err = parse_error("sphere { center 0 0 0 radius 1 material red }");
+        assert_eq!((err.line, err.column), (1, 41));
+    }
+
+    #[test]
+    fn missing_property() {
+        let err = parse_error("material red lambertian { }\nsphere { material red }");
+        assert_eq!((err.line, err.column), (1, 25));
+
+        let err = parse_error("material red lambertian { albedo 1 0 0 }\nsphere { material red }");
+        assert_eq!((err.line, err.column), (2, 8));
+        assert!(err.message.contains("radius"));
+    }
+
+    #[test]
+    fn unknown_tone_map() {
+        let err = parse_error("image { tone_map filmic }");
+        assert_eq!((err.line, err.column), (1, 18));
+    }
+
+    #[test]
+    fn invalid_white_point() {
+        let err = parse_error("image { tone_map extended_reinhard white 0 }");
+        assert_eq!((err.line, err.column), (1, 42));
+        let err = parse_error("image { white -2 tone_map extended_reinhard }");
+        assert!(err.message.contains("positive"));
+        let err = parse_error("image { white 4 tone_map aces }");
+        assert_eq!((err.line, err.column), (1, 15));
+        assert!(err.message.contains("extended_reinhard"));
+    }
+
+    #[test]
+    fn invalid_image_size() {
+        let err = parse_error("image { width 0 }");
+        assert_eq!((err.line, err.column), (1, 15));
+        let err = parse_error("image { aspect_ratio 0 }");
+        assert_eq!((err.line, err.column), (1, 22));
+        let err = parse_error("image { aspect_ratio -16 / 9 }");
+        assert!(err.message.contains("aspect ratio"));
+    }
+
+    #[test]
+    fn degenerate_camera() {
+        let err = parse_error("camera {\n  look_from 1 2 3 look_at 1 2 3\n}");
+        assert_eq!((err.line, err.column), (1, 8));
+        assert!(err.message.contains("same point"));
+
+        let err = parse_error("camera { look_from 0 0 0 look_at 0 -5 0 }");
+        assert!(err.message.contains("parallel"));
+        let err = parse_error("camera { vup 0 0 2 }");
+        assert!(err.message.contains("parallel"));
+
+        let err = parse_error("camera { vfov 0 }");
+        assert_eq!((err.line, err.column), (1, 15));
+        let err = parse_error("camera { vfov 180 }");
+        assert!(err.message.contains("vfov"));
+    }
+
+    #[test]
+    fn partial_triangle_normals() {
+        let err = parse_error(
+            "material red lambertian { albedo 1 0 0 }
+             triangle { a 0 0 0 b 1 0 0 c 0 1 0 na 0 0 1 material red }",
+        );
+        assert_eq!(err.line, 2);
+        assert!(err.message.contains("nb"));
+    }
+
+    #[test]
+    fn expected_number() {
+        let err = parse_error("image { width wide }");
+        assert_eq!((err.line, err.column), (1, 15));
+    }
+
+    #[test]
+    fn unterminated_block() {
+        let err = parse_error("camera { vfov 40");
+        assert_eq!((err.line, err.column), (1, 17));
+    }
+
+    #[test]
+    fn mesh_relative_to_directory() {
+        let source = "material red lambertian { albedo 1 0 0 }\n\
+                      mesh { file \"models/pyramid.obj\" }\n\
+                      mesh { file \"models/pyramid.obj\" material red }";
+        let file = parse_in(source, Path::new("scenes")).unwrap();
+        let Shapes::Bvh(bvh) = &file.scene.objects().objects()[0] else {
+            panic!("scene is not under a bvh");
+        };
+        let meshes: Vec<_> = bvh
+            .items()
+            .iter()
+            .filter_map(|shape| match shape {
+                Shapes::Mesh(mesh) => Some(mesh.data()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(meshes.len(), 2);
+        assert_eq!(meshes[0].faces.len(), 6);
+        assert!(matches!(meshes[0].materials[1], Materials::Metal(_)));
+        assert_eq!(meshes[1].materials.len(), 1);
+    }
+
+    #[test]
+    fn parallel_box_axes() {
+        let err = parse_error(
+            "material red lambertian { albedo 1 0 0 }
+             oriented_box { center 0 0 0 size 1 1 1 x_axis 0 2 0 material red }",
+        );
+        assert_eq!((err.line, err.column), (2, 27));
+    }
+
+    #[test]
+    fn instances() {
+        let file = parse(
+            "material red lambertian { albedo 1 0 0 }
+             object pair {
+                 sphere { center -1 0 0 radius 0.5 material red }
+                 sphere { center 1 0 0 radius 0.5 material red }
+             }
+             instance pair { scale 2 2 2 translate 0 0 -5 }
+             instance pair { rotate 0 1 0 90 }",
+        )
+        .unwrap();
+
+        let ray = Ray::new(Vector3f::new(2.0, 0.0, 0.0), Vector3f::new(0.0, 0.0, -1.0));
+        let hit = file
+            .scene
+            .intersect(&ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        assert!(utils::is_close_to(&hit.time, &4.0));
+    }
+
+    #[test]
+    fn object_errors() {
+        let err = parse_error("instance missing { }");
+        assert_eq!((err.line, err.column), (1, 10));
+
+        let err = parse_error("object a { light point { position 0 0 0 intensity 1 1 1 } }");
+        assert_eq!((err.line, err.column), (1, 12));
+
+        let err = parse_error(
+            "material red lambertian { albedo 1 0 0 }
+             object a { sphere { center 0 0 0 radius 1 material red } }
+             instance a { scale 1 0 1 }",
+        );
+        assert_eq!((err.line, err.column), (3, 33));
+    }
+
+    #[test]
+    fn missing_mesh_file() {
+        let err = parse_error("mesh { file \"missing.obj\" }");
+        assert_eq!((err.line, err.column), (1, 13));
+    }
+}
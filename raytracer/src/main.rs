@@ -1,37 +1,41 @@
-#![allow(dead_code)]
-
-mod image;
-mod interval;
-mod math;
-
-use raytracer::{
-    camera::Camera,
-    image::color,
-    math::Vector3f,
-    shapes::{shapes::Shapes, sphere::Sphere},
-};
-
-use std::fs::OpenOptions;
-
-fn main() -> std::io::Result<()> {
-    println!("Hello, world!");
-
-    let aspect_ratio = 16.0 / 9.0;
-    let image_width: u16 = 1024;
-
-    let mut file = OpenOptions::new()
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open("target/image.ppm")?;
-
-    let sphere = Sphere::new(0.5, Vector3f::new(0.0, 0.0, -1.0), color::RED);
-    let ground = Sphere::new(100.0, Vector3f::new(0.0, -110.5, -10.0), color::GREEN);
+use raytracer::{image::ImageFormat, loader::scene_file};
+
+use std::{env, io, path::Path, process::ExitCode};
+
+const USAGE: &str = "usage: raytracer [scene file] [output file]";
+
+fn run(scene_path: &str, output_path: &str) -> io::Result<()> {
+    // fail before rendering rather than after
+    if ImageFormat::from_path(Path::new(output_path)).is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{output_path}: unsupported output format, use .ppm, .png, .hdr or .pfm"),
+        ));
+    }
+
+    let scene_file::SceneFile {
+        camera,
+        scene,
+        display,
+    } = scene_file::load(scene_path)?;
+    camera.render(&scene).save(output_path, &display)
+}
 
-    let camera = Camera::new(aspect_ratio, image_width);
-    camera.render(
-        &mut file,
-        &vec![Shapes::Sphere(sphere), Shapes::Sphere(ground)],
-    )?;
-    Ok(())
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() > 2 || args.iter().any(|a| a == "-h" || a == "--help") {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    }
+
+    let scene_path = args.first().map_or("scenes/spheres.scene", String::as_str);
+    let output_path = args.get(1).map_or("target/image.ppm", String::as_str);
+
+    match run(scene_path, output_path) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
 }
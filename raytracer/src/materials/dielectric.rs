@@ -0,0 +1,71 @@
+use crate::{
+    geometry::intersectable::HitRecord,
+    image::color::WHITE,
+    materials::material::{Material, ScatterRecord},
+    math::{Ray, utils},
+};
+
+/// Clear material like glass or water that refracts following Snell's law
+#[derive(Debug, Clone)]
+pub struct Dielectric {
+    /// index of refraction relative to the enclosing medium
+    refraction_index: f64,
+}
+
+impl Dielectric {
+    pub fn new(refraction_index: f64) -> Self {
+        Self { refraction_index }
+    }
+}
+
+/// Schlick's approximation for the reflectance at a given angle
+fn reflectance(cosine: f64, refraction_index: f64) -> f64 {
+    let r0 = (1.0 - refraction_index) / (1.0 + refraction_index);
+    let r0 = r0 * r0;
+    r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
+}
+
+impl Material for Dielectric {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let ri = if hit.is_front_face {
+            1.0 / self.refraction_index
+        } else {
+            self.refraction_index
+        };
+
+        let unit_direction = ray.direction.normalize();
+        let cos_theta = (-unit_direction).dot(&hit.normal).min(1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+
+        // total internal reflection
+        let cannot_refract = ri * sin_theta > 1.0;
+
+        let direction =
+            if cannot_refract || reflectance(cos_theta, ri) > utils::random_range(0.0, 1.0) {
+                unit_direction.reflect(&hit.normal)
+            } else {
+                unit_direction.refract(&hit.normal, ri)
+            };
+
+        Some(ScatterRecord {
+            attenuation: WHITE,
+            scattered: Ray::new(hit.point, direction).with_time(hit.ray.time),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reflectance_at_normal_incidence() {
+        // glass reflects about 4% head on
+        assert!(utils::is_close_to(&reflectance(1.0, 1.5), &0.04));
+    }
+
+    #[test]
+    fn reflectance_at_grazing_angle() {
+        assert!(utils::is_close_to(&reflectance(0.0, 1.5), &1.0));
+    }
+}
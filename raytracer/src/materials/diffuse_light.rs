@@ -0,0 +1,36 @@
+use crate::{
+    geometry::intersectable::HitRecord,
+    image::color::Colorf64,
+    materials::material::{Material, ScatterRecord},
+    math::Ray,
+    textures::texture::{Texture, Textures},
+};
+
+/// Surface glowing with the same radiance in every direction from both
+/// sides, shapes made of it become area lights. It reflects nothing
+#[derive(Debug, Clone)]
+pub struct DiffuseLight {
+    radiance: Textures,
+}
+
+impl DiffuseLight {
+    /// radiance may go above 1 for lights brighter than white
+    pub fn new(radiance: Colorf64) -> Self {
+        Self::textured(radiance.into())
+    }
+
+    /// radiance varying over the surface
+    pub fn textured(radiance: Textures) -> Self {
+        Self { radiance }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _ray: &Ray, _hit: &HitRecord) -> Option<ScatterRecord> {
+        None
+    }
+
+    fn emitted(&self, hit: &HitRecord) -> Colorf64 {
+        self.radiance.value(hit.u, hit.v, &hit.point)
+    }
+}
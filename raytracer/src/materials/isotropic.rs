@@ -0,0 +1,34 @@
+use crate::{
+    geometry::intersectable::HitRecord,
+    image::color::Colorf64,
+    materials::material::{Material, ScatterRecord},
+    math::{Ray, Vector3f},
+    textures::texture::{Texture, Textures},
+};
+
+/// Phase function of participating media, light scatters equally in every
+/// direction no matter where it came from
+#[derive(Debug, Clone)]
+pub struct Isotropic {
+    albedo: Textures,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Colorf64) -> Self {
+        Self::textured(albedo.into())
+    }
+
+    /// albedo varying through the medium
+    pub fn textured(albedo: Textures) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(&self, _ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        Some(ScatterRecord {
+            attenuation: self.albedo.value(hit.u, hit.v, &hit.point),
+            scattered: Ray::new(hit.point, Vector3f::random_unit_vector()).with_time(hit.ray.time),
+        })
+    }
+}
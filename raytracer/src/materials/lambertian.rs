@@ -0,0 +1,44 @@
+use crate::{
+    geometry::intersectable::HitRecord,
+    image::color::Colorf64,
+    materials::material::{Material, ScatterRecord},
+    math::{Ray, Vector3f, utils},
+    textures::texture::{Texture, Textures},
+};
+
+/// Ideal diffuse surface, scatters with a cosine distribution around the normal
+#[derive(Debug, Clone)]
+pub struct Lambertian {
+    albedo: Textures,
+}
+
+impl Lambertian {
+    pub fn new(albedo: Colorf64) -> Self {
+        Self::textured(albedo.into())
+    }
+
+    /// albedo varying over the surface
+    pub fn textured(albedo: Textures) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Material for Lambertian {
+    fn scatter(&self, _ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let mut direction = hit.normal + Vector3f::random_unit_vector();
+
+        // the random vector can cancel out the normal
+        if utils::near_zero_vector(&direction) {
+            direction = hit.normal;
+        }
+
+        Some(ScatterRecord {
+            attenuation: self.albedo.value(hit.u, hit.v, &hit.point),
+            scattered: Ray::new(hit.point, direction).with_time(hit.ray.time),
+        })
+    }
+
+    fn diffuse(&self, hit: &HitRecord) -> Option<Colorf64> {
+        Some(self.albedo.value(hit.u, hit.v, &hit.point))
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    geometry::intersectable::HitRecord,
+    image::{Color, color},
+    materials::{
+        dielectric::Dielectric, diffuse_light::DiffuseLight, isotropic::Isotropic,
+        lambertian::Lambertian, metal::Metal,
+    },
+    math::Ray,
+};
+
+/// Result of a ray scattering off a surface
+pub struct ScatterRecord {
+    /// how much of the incoming light each channel keeps
+    pub attenuation: Color<f64>,
+    /// the ray leaving the surface
+    pub scattered: Ray,
+}
+
+pub trait Material {
+    /// Scatter the incoming ray at the hit point, returns None if the ray is absorbed
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord>;
+
+    /// Albedo of surfaces that reflect diffusely, these get direct light from
+    /// point lights since a scattered ray can never hit one
+    fn diffuse(&self, _hit: &HitRecord) -> Option<Color<f64>> {
+        None
+    }
+
+    /// Light given off by the surface at the hit point
+    fn emitted(&self, _hit: &HitRecord) -> Color<f64> {
+        color::BLACK
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Materials {
+    Lambertian(Lambertian),
+    Metal(Metal),
+    Dielectric(Dielectric),
+    DiffuseLight(DiffuseLight),
+    Isotropic(Isotropic),
+}
+
+impl Material for Materials {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        match self {
+            Materials::Lambertian(m) => m.scatter(ray, hit),
+            Materials::Metal(m) => m.scatter(ray, hit),
+            Materials::Dielectric(m) => m.scatter(ray, hit),
+            Materials::DiffuseLight(m) => m.scatter(ray, hit),
+            Materials::Isotropic(m) => m.scatter(ray, hit),
+        }
+    }
+
+    fn diffuse(&self, hit: &HitRecord) -> Option<Color<f64>> {
+        match self {
+            Materials::Lambertian(m) => m.diffuse(hit),
+            Materials::Metal(m) => m.diffuse(hit),
+            Materials::Dielectric(m) => m.diffuse(hit),
+            Materials::DiffuseLight(m) => m.diffuse(hit),
+            Materials::Isotropic(m) => m.diffuse(hit),
+        }
+    }
+
+    fn emitted(&self, hit: &HitRecord) -> Color<f64> {
+        match self {
+            Materials::Lambertian(m) => m.emitted(hit),
+            Materials::Metal(m) => m.emitted(hit),
+            Materials::Dielectric(m) => m.emitted(hit),
+            Materials::DiffuseLight(m) => m.emitted(hit),
+            Materials::Isotropic(m) => m.emitted(hit),
+        }
+    }
+}
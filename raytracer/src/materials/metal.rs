@@ -0,0 +1,46 @@
+use crate::{
+    geometry::intersectable::HitRecord,
+    image::color::Colorf64,
+    materials::material::{Material, ScatterRecord},
+    math::{Ray, Vector3f},
+    textures::texture::{Texture, Textures},
+};
+
+/// Reflective surface, fuzz perturbs the mirror direction for a brushed look
+#[derive(Debug, Clone)]
+pub struct Metal {
+    albedo: Textures,
+    fuzz: f64,
+}
+
+impl Metal {
+    /// fuzz is clamped to [0, 1], 0 is a perfect mirror
+    pub fn new(albedo: Colorf64, fuzz: f64) -> Self {
+        Self::textured(albedo.into(), fuzz)
+    }
+
+    /// albedo varying over the surface
+    pub fn textured(albedo: Textures, fuzz: f64) -> Self {
+        Self {
+            albedo,
+            fuzz: fuzz.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl Material for Metal {
+    fn scatter(&self, ray: &Ray, hit: &HitRecord) -> Option<ScatterRecord> {
+        let reflected = ray.direction.normalize().reflect(&hit.normal)
+            + Vector3f::random_unit_vector() * self.fuzz;
+
+        // fuzz pushed the ray below the surface, absorb it
+        if reflected.dot(&hit.normal) <= 0.0 {
+            return None;
+        }
+
+        Some(ScatterRecord {
+            attenuation: self.albedo.value(hit.u, hit.v, &hit.point),
+            scattered: Ray::new(hit.point, reflected).with_time(hit.ray.time),
+        })
+    }
+}
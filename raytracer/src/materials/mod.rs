@@ -0,0 +1,6 @@
+pub mod dielectric;
+pub mod diffuse_light;
+pub mod isotropic;
+pub mod lambertian;
+pub mod material;
+pub mod metal;
@@ -0,0 +1,68 @@
+use crate::math::Vector3f;
+
+/// Orthonormal basis of a local space, converts directions between it and world space
+#[derive(Debug, Clone, Copy)]
+pub struct Frame {
+    pub x: Vector3f,
+    pub y: Vector3f,
+    pub z: Vector3f,
+}
+
+impl Frame {
+    /// any frame whose z axis points along z
+    pub fn from_z(z: Vector3f) -> Self {
+        let z = z.normalize();
+        let (x, y) = z.orthonormal_basis();
+        Self { x, y, z }
+    }
+
+    /// x is kept as given and y is made perpendicular to it, they must not be parallel
+    pub fn from_xy(x: Vector3f, y: Vector3f) -> Self {
+        let x = x.normalize();
+        let z = x.cross(&y).normalize();
+        Self {
+            x,
+            y: z.cross(&x),
+            z,
+        }
+    }
+
+    pub fn to_local(&self, v: &Vector3f) -> Vector3f {
+        Vector3f::new(v.dot(&self.x), v.dot(&self.y), v.dot(&self.z))
+    }
+
+    pub fn to_world(&self, v: &Vector3f) -> Vector3f {
+        self.x * v.x + self.y * v.y + self.z * v.z
+    }
+
+    /// Half extents along the world axes of a local box with the given half extents
+    pub fn world_extent(&self, half: &Vector3f) -> Vector3f {
+        let (x, y, z) = (self.x, self.y, self.z);
+        Vector3f::new(
+            x.x.abs() * half.x + y.x.abs() * half.y + z.x.abs() * half.z,
+            x.y.abs() * half.x + y.y.abs() * half.y + z.y.abs() * half.z,
+            x.z.abs() * half.x + y.z.abs() * half.y + z.z.abs() * half.z,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let frame = Frame::from_z(Vector3f::new(1.0, 2.0, 3.0));
+        let v = Vector3f::new(-0.5, 4.0, 2.0);
+        assert_eq!(frame.to_world(&frame.to_local(&v)), v);
+        assert_eq!(frame.to_local(&frame.z), Vector3f::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn from_xy_orthogonalizes() {
+        let frame = Frame::from_xy(Vector3f::new(2.0, 0.0, 0.0), Vector3f::new(1.0, 1.0, 0.0));
+        assert_eq!(frame.x, Vector3f::new(1.0, 0.0, 0.0));
+        assert_eq!(frame.y, Vector3f::new(0.0, 1.0, 0.0));
+        assert_eq!(frame.z, Vector3f::new(0.0, 0.0, 1.0));
+    }
+}
@@ -0,0 +1,303 @@
+use std::ops::Mul;
+
+use crate::math::{Normal3f, Point3f, Vector3f};
+
+/// Determinants smaller than this make a matrix singular
+const SINGULAR_EPSILON: f64 = 1e-12;
+
+/// 4x4 matrix in row major order, points and vectors are columns on the right
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix4 {
+    pub m: [[f64; 4]; 4],
+}
+
+pub const IDENTITY: Matrix4 = Matrix4::new([
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+]);
+
+impl Matrix4 {
+    pub const fn new(m: [[f64; 4]; 4]) -> Self {
+        Self { m }
+    }
+
+    pub fn translation(offset: Vector3f) -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, offset.x],
+            [0.0, 1.0, 0.0, offset.y],
+            [0.0, 0.0, 1.0, offset.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn scaling(factors: Vector3f) -> Self {
+        Self::new([
+            [factors.x, 0.0, 0.0, 0.0],
+            [0.0, factors.y, 0.0, 0.0],
+            [0.0, 0.0, factors.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// counter clockwise rotation by angle radians around axis, looking down the axis
+    pub fn rotation(axis: Vector3f, angle: f64) -> Self {
+        let a = axis.normalize();
+        let (sin, cos) = angle.sin_cos();
+        let t = 1.0 - cos;
+        Self::new([
+            [
+                t * a.x * a.x + cos,
+                t * a.x * a.y - sin * a.z,
+                t * a.x * a.z + sin * a.y,
+                0.0,
+            ],
+            [
+                t * a.x * a.y + sin * a.z,
+                t * a.y * a.y + cos,
+                t * a.y * a.z - sin * a.x,
+                0.0,
+            ],
+            [
+                t * a.x * a.z - sin * a.y,
+                t * a.y * a.z + sin * a.x,
+                t * a.z * a.z + cos,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.m[j][i];
+            }
+        }
+        Self::new(m)
+    }
+
+    /// Gauss-Jordan elimination with partial pivoting, None when singular
+    pub fn inverse(&self) -> Option<Self> {
+        let mut a = self.m;
+        let mut inverse = IDENTITY.m;
+
+        for column in 0..4 {
+            let pivot = (column..4)
+                .max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))
+                .unwrap_or(column);
+            if a[pivot][column].abs() < SINGULAR_EPSILON {
+                return None;
+            }
+            a.swap(column, pivot);
+            inverse.swap(column, pivot);
+
+            let scale = 1.0 / a[column][column];
+            for j in 0..4 {
+                a[column][j] *= scale;
+                inverse[column][j] *= scale;
+            }
+
+            for row in 0..4 {
+                if row == column {
+                    continue;
+                }
+                let factor = a[row][column];
+                for j in 0..4 {
+                    a[row][j] -= factor * a[column][j];
+                    inverse[row][j] -= factor * inverse[column][j];
+                }
+            }
+        }
+
+        Some(Self::new(inverse))
+    }
+
+    /// the point including the translation
+    pub fn transform_point(&self, p: &Point3f) -> Point3f {
+        let m = &self.m;
+        let x = m[0][0] * p.x + m[0][1] * p.y + m[0][2] * p.z + m[0][3];
+        let y = m[1][0] * p.x + m[1][1] * p.y + m[1][2] * p.z + m[1][3];
+        let z = m[2][0] * p.x + m[2][1] * p.y + m[2][2] * p.z + m[2][3];
+        let w = m[3][0] * p.x + m[3][1] * p.y + m[3][2] * p.z + m[3][3];
+        if w == 1.0 {
+            Vector3f::new(x, y, z)
+        } else {
+            Vector3f::new(x / w, y / w, z / w)
+        }
+    }
+
+    /// the direction, unaffected by the translation
+    pub fn transform_vector(&self, v: &Vector3f) -> Vector3f {
+        let m = &self.m;
+        Vector3f::new(
+            m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+            m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+            m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+        )
+    }
+}
+
+impl Mul for Matrix4 {
+    type Output = Matrix4;
+
+    fn mul(self, rhs: Matrix4) -> Self::Output {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.m[i][k] * rhs.m[k][j]).sum();
+            }
+        }
+        Matrix4::new(m)
+    }
+}
+
+/// Affine transform with its inverse, so both directions are cheap
+#[derive(Debug, Clone, Copy)]
+pub struct Transform {
+    pub matrix: Matrix4,
+    pub inverse: Matrix4,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            matrix: IDENTITY,
+            inverse: IDENTITY,
+        }
+    }
+}
+
+impl Transform {
+    /// None when the matrix can not be inverted
+    pub fn from_matrix(matrix: Matrix4) -> Option<Self> {
+        Some(Self {
+            inverse: matrix.inverse()?,
+            matrix,
+        })
+    }
+
+    pub fn translation(offset: Vector3f) -> Self {
+        Self {
+            matrix: Matrix4::translation(offset),
+            inverse: Matrix4::translation(-offset),
+        }
+    }
+
+    /// factors must not be zero, the scaling would not be invertible
+    pub fn scaling(factors: Vector3f) -> Self {
+        assert!(
+            factors.x != 0.0 && factors.y != 0.0 && factors.z != 0.0,
+            "scale factors must not be zero, got {factors:?}"
+        );
+        Self {
+            matrix: Matrix4::scaling(factors),
+            inverse: Matrix4::scaling(Vector3f::new(
+                1.0 / factors.x,
+                1.0 / factors.y,
+                1.0 / factors.z,
+            )),
+        }
+    }
+
+    /// counter clockwise rotation by angle degrees around axis
+    pub fn rotation(axis: Vector3f, degrees: f64) -> Self {
+        let matrix = Matrix4::rotation(axis, degrees.to_radians());
+        Self {
+            inverse: matrix.transpose(),
+            matrix,
+        }
+    }
+
+    /// this transform followed by next
+    pub fn then(&self, next: &Transform) -> Self {
+        Self {
+            matrix: next.matrix * self.matrix,
+            inverse: self.inverse * next.inverse,
+        }
+    }
+
+    pub fn point(&self, p: &Point3f) -> Point3f {
+        self.matrix.transform_point(p)
+    }
+
+    pub fn vector(&self, v: &Vector3f) -> Vector3f {
+        self.matrix.transform_vector(v)
+    }
+
+    /// Normals transform with the inverse transpose to stay perpendicular to
+    /// the surface under non uniform scaling, the result is not normalized
+    pub fn normal(&self, n: &Normal3f) -> Normal3f {
+        self.inverse.transpose().transform_vector(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_matrix_eq(a: &Matrix4, b: &Matrix4) {
+        for i in 0..4 {
+            for j in 0..4 {
+                assert!((a.m[i][j] - b.m[i][j]).abs() < 1e-9, "{a:?} != {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn inverse() {
+        let m = Matrix4::translation(Vector3f::new(1.0, -2.0, 3.0))
+            * Matrix4::rotation(Vector3f::new(1.0, 1.0, 0.0), 0.7)
+            * Matrix4::scaling(Vector3f::new(2.0, 0.5, 3.0));
+        let inverse = m.inverse().unwrap();
+        assert_matrix_eq(&(m * inverse), &IDENTITY);
+        assert_matrix_eq(&(inverse * m), &IDENTITY);
+    }
+
+    #[test]
+    fn singular() {
+        assert!(
+            Matrix4::scaling(Vector3f::new(1.0, 0.0, 1.0))
+                .inverse()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn rotation() {
+        let t = Transform::rotation(Vector3f::new(0.0, 0.0, 1.0), 90.0);
+        assert_eq!(
+            t.vector(&Vector3f::new(1.0, 0.0, 0.0)),
+            Vector3f::new(0.0, 1.0, 0.0)
+        );
+        assert_matrix_eq(&(t.matrix * t.inverse), &IDENTITY);
+    }
+
+    #[test]
+    fn then_applies_in_order() {
+        let t = Transform::scaling(Vector3f::new(2.0, 2.0, 2.0))
+            .then(&Transform::translation(Vector3f::new(1.0, 0.0, 0.0)));
+        let p = Vector3f::new(1.0, 1.0, 1.0);
+        assert_eq!(t.point(&p), Vector3f::new(3.0, 2.0, 2.0));
+        assert_eq!(t.inverse.transform_point(&t.point(&p)), p);
+        // vectors ignore the translation
+        assert_eq!(t.vector(&p), Vector3f::new(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn normal_under_non_uniform_scale() {
+        // a 45 degree slope squashed along y keeps its normal perpendicular
+        let t = Transform::scaling(Vector3f::new(1.0, 0.5, 1.0));
+        let tangent = t.vector(&Vector3f::new(1.0, 1.0, 0.0));
+        let normal = t.normal(&Vector3f::new(1.0, -1.0, 0.0));
+        assert!(tangent.dot(&normal).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "scale factors must not be zero")]
+    fn zero_scaling() {
+        let _ = Transform::scaling(Vector3f::new(1.0, 0.0, 1.0));
+    }
+}
@@ -1,3 +1,7 @@
+pub mod frame;
+pub mod matrix4;
+pub mod noise;
+pub mod polynomial;
 pub mod ray;
 pub mod utils;
 
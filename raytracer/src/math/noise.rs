@@ -0,0 +1,146 @@
+use rand::{Rng, SeedableRng, rngs::SmallRng, seq::SliceRandom};
+
+use crate::math::{Point3f, Vector3f};
+
+/// Lattice cells before the noise repeats along each axis
+const POINT_COUNT: usize = 256;
+
+/// Gradient noise, smooth random values that vary on a scale of one unit. The
+/// same seed always gives the same noise, independent of the render seed
+#[derive(Debug, Clone)]
+pub struct Perlin {
+    gradients: Vec<Vector3f>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = SmallRng::seed_from_u64(seed);
+
+        let gradients = (0..POINT_COUNT)
+            .map(|_| {
+                loop {
+                    let v = Vector3f::new(
+                        rng.random_range(-1.0..1.0),
+                        rng.random_range(-1.0..1.0),
+                        rng.random_range(-1.0..1.0),
+                    );
+                    // uniform directions need points inside the sphere
+                    let length = v.norm_squared();
+                    if length > 1e-6 && length <= 1.0 {
+                        break v.normalize();
+                    }
+                }
+            })
+            .collect();
+
+        let mut permutation = || {
+            let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+            p.shuffle(&mut rng);
+            p
+        };
+
+        Self {
+            gradients,
+            perm_x: permutation(),
+            perm_y: permutation(),
+            perm_z: permutation(),
+        }
+    }
+
+    fn gradient(&self, i: i64, j: i64, k: i64) -> Vector3f {
+        let wrap = |n: i64| n.rem_euclid(POINT_COUNT as i64) as usize;
+        self.gradients[self.perm_x[wrap(i)] ^ self.perm_y[wrap(j)] ^ self.perm_z[wrap(k)]]
+    }
+
+    /// Noise at p in [-1, 1], zero on the integer lattice
+    pub fn noise(&self, p: &Point3f) -> f64 {
+        let (i, j, k) = (p.x.floor(), p.y.floor(), p.z.floor());
+        let local = Vector3f::new(p.x - i, p.y - j, p.z - k);
+        let (i, j, k) = (i as i64, j as i64, k as i64);
+
+        // Hermite smoothing keeps the lattice from showing through
+        let smooth = |t: f64| t * t * (3.0 - 2.0 * t);
+        let (u, v, w) = (smooth(local.x), smooth(local.y), smooth(local.z));
+
+        // trilinear blend of the corner gradients dotted with the offset to p
+        let mut sum = 0.0;
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let corner = Vector3f::new(f64::from(di), f64::from(dj), f64::from(dk));
+                    let weight = (if di == 1 { u } else { 1.0 - u })
+                        * (if dj == 1 { v } else { 1.0 - v })
+                        * (if dk == 1 { w } else { 1.0 - w });
+                    let gradient =
+                        self.gradient(i + i64::from(di), j + i64::from(dj), k + i64::from(dk));
+                    sum += weight * gradient.dot(&(local - corner));
+                }
+            }
+        }
+        // a unit gradient dotted with offsets inside the cell peaks at sqrt(3) / 2
+        (sum * 2.0 / 3.0_f64.sqrt()).clamp(-1.0, 1.0)
+    }
+
+    /// Fractal Brownian motion, octaves of noise at doubling frequency and
+    /// halving amplitude, in [-1, 1]
+    pub fn fbm(&self, p: &Point3f, octaves: u32) -> f64 {
+        self.octaves(p, octaves, |n| n)
+    }
+
+    /// Like fbm with the absolute value of each octave, the creases give
+    /// marble veins and flames, in [0, 1]
+    pub fn turbulence(&self, p: &Point3f, octaves: u32) -> f64 {
+        self.octaves(p, octaves, f64::abs)
+    }
+
+    fn octaves(&self, p: &Point3f, octaves: u32, shape: impl Fn(f64) -> f64) -> f64 {
+        let mut sum = 0.0;
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut point = *p;
+        for _ in 0..octaves.max(1) {
+            sum += amplitude * shape(self.noise(&point));
+            total += amplitude;
+            amplitude *= 0.5;
+            point = point * 2.0;
+        }
+        sum / total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_noise() {
+        let p = Vector3f::new(1.3, -2.7, 0.4);
+        assert_eq!(Perlin::new(7).noise(&p), Perlin::new(7).noise(&p));
+        assert_ne!(Perlin::new(7).noise(&p), Perlin::new(8).noise(&p));
+    }
+
+    #[test]
+    fn zero_on_lattice() {
+        let perlin = Perlin::new(1);
+        assert_eq!(perlin.noise(&Vector3f::new(3.0, -1.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn continuous_and_bounded() {
+        let perlin = Perlin::new(3);
+        let mut previous = perlin.noise(&Vector3f::new(0.0, 0.5, 0.5));
+        for step in 1..1000 {
+            let p = Vector3f::new(f64::from(step) * 0.01, 0.5, 0.5);
+            let n = perlin.noise(&p);
+            assert!((-1.0..=1.0).contains(&n));
+            assert!((n - previous).abs() < 0.05);
+            previous = n;
+
+            assert!((-1.0..=1.0).contains(&perlin.fbm(&p, 5)));
+            assert!((0.0..=1.0).contains(&perlin.turbulence(&p, 5)));
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! Real roots of low degree polynomials, closed form after Schwarze,
+//! "Cubic and Quartic Roots", Graphics Gems I
+
+use std::f64::consts::PI;
+
+/// Coefficients smaller than this are treated as zero
+const EPSILON: f64 = 1e-9;
+/// Newton steps used to polish the quartic roots
+const POLISH_STEPS: usize = 2;
+
+fn is_zero(x: f64) -> bool {
+    x.abs() < EPSILON
+}
+
+/// Real roots of a x^2 + b x + c in ascending order, a linear equation when a is 0
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if is_zero(a) {
+        return if is_zero(b) { Vec::new() } else { vec![-c / b] };
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return Vec::new();
+    }
+
+    // avoids cancellation between b and the square root
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+    let mut roots = if q == 0.0 {
+        vec![0.0]
+    } else {
+        vec![q / a, c / q]
+    };
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// Real roots of x^3 + a x^2 + b x + c, unsorted
+fn solve_normed_cubic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    // substitute x = y - a / 3 to get y^3 + 3 p y + 2 q = 0
+    let a_squared = a * a;
+    let p = (-a_squared / 3.0 + b) / 3.0;
+    let q = (2.0 / 27.0 * a * a_squared - a * b / 3.0 + c) / 2.0;
+
+    let p_cubed = p * p * p;
+    let discriminant = q * q + p_cubed;
+
+    let roots = if is_zero(discriminant) {
+        if is_zero(q) {
+            vec![0.0]
+        } else {
+            let u = (-q).cbrt();
+            vec![2.0 * u, -u]
+        }
+    } else if discriminant < 0.0 {
+        // three real roots
+        let phi = (-q / (-p_cubed).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        let t = 2.0 * (-p).sqrt();
+        vec![
+            t * phi.cos(),
+            -t * (phi + PI / 3.0).cos(),
+            -t * (phi - PI / 3.0).cos(),
+        ]
+    } else {
+        let sqrt_d = discriminant.sqrt();
+        vec![(sqrt_d - q).cbrt() - (sqrt_d + q).cbrt()]
+    };
+
+    roots.into_iter().map(|y| y - a / 3.0).collect()
+}
+
+/// Real roots of a x^3 + b x^2 + c x + d in ascending order
+pub fn solve_cubic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    if is_zero(a) {
+        return solve_quadratic(b, c, d);
+    }
+
+    let mut roots = solve_normed_cubic(b / a, c / a, d / a);
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// Real roots of a x^4 + b x^3 + c x^2 + d x + e in ascending order, refined
+/// with Newton's method since the closed form loses precision
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    if is_zero(a) {
+        return solve_cubic(b, c, d, e);
+    }
+
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+
+    // substitute x = y - b / 4 to get y^4 + p y^2 + q y + r = 0
+    let b_squared = b * b;
+    let p = -3.0 / 8.0 * b_squared + c;
+    let q = b_squared * b / 8.0 - b * c / 2.0 + d;
+    let r = -3.0 / 256.0 * b_squared * b_squared + b_squared * c / 16.0 - b * d / 4.0 + e;
+
+    let mut roots = if is_zero(r) {
+        // y (y^3 + p y + q) = 0
+        let mut roots = solve_normed_cubic(0.0, p, q);
+        roots.push(0.0);
+        roots
+    } else {
+        // any real root of the resolvent cubic splits the quartic into two
+        // quadratics, the largest one is the most stable
+        let z = solve_normed_cubic(-p / 2.0, -r, r * p / 2.0 - q * q / 8.0)
+            .into_iter()
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        let u = z * z - r;
+        let v = 2.0 * z - p;
+        let u = if is_zero(u) {
+            0.0
+        } else if u > 0.0 {
+            u.sqrt()
+        } else {
+            return Vec::new();
+        };
+        let v = if is_zero(v) {
+            0.0
+        } else if v > 0.0 {
+            v.sqrt()
+        } else {
+            return Vec::new();
+        };
+        let v = if q < 0.0 { -v } else { v };
+
+        let mut roots = solve_quadratic(1.0, v, z - u);
+        roots.extend(solve_quadratic(1.0, -v, z + u));
+        roots
+    };
+
+    let value = |x: f64| (((x + b) * x + c) * x + d) * x + e;
+    let slope = |x: f64| ((4.0 * x + 3.0 * b) * x + 2.0 * c) * x + d;
+    for root in &mut roots {
+        *root -= b / 4.0;
+        for _ in 0..POLISH_STEPS {
+            let derivative = slope(*root);
+            if derivative != 0.0 {
+                *root -= value(*root) / derivative;
+            }
+        }
+    }
+
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_roots(actual: Vec<f64>, expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{actual:?} != {expected:?}");
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-6, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn quadratic() {
+        assert_roots(solve_quadratic(1.0, -3.0, 2.0), &[1.0, 2.0]);
+        assert_roots(solve_quadratic(2.0, 0.0, 2.0), &[]);
+        assert_roots(solve_quadratic(0.0, 2.0, -1.0), &[0.5]);
+    }
+
+    #[test]
+    fn cubic() {
+        // (x - 1)(x - 2)(x - 3)
+        assert_roots(solve_cubic(1.0, -6.0, 11.0, -6.0), &[1.0, 2.0, 3.0]);
+        // (x - 2)(x^2 + 1)
+        assert_roots(solve_cubic(2.0, -4.0, 2.0, -4.0), &[2.0]);
+    }
+
+    #[test]
+    fn quartic() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        assert_roots(
+            solve_quartic(1.0, -10.0, 35.0, -50.0, 24.0),
+            &[1.0, 2.0, 3.0, 4.0],
+        );
+        // (x^2 - 4)(x^2 + 1)
+        assert_roots(solve_quartic(3.0, 0.0, -9.0, 0.0, -12.0), &[-2.0, 2.0]);
+        // x^4 + 1 has no real roots
+        assert_roots(solve_quartic(1.0, 0.0, 0.0, 0.0, 1.0), &[]);
+        // x (x - 1)(x + 2)(x - 5)
+        assert_roots(
+            solve_quartic(1.0, -4.0, -7.0, 10.0, 0.0),
+            &[-2.0, 0.0, 1.0, 5.0],
+        );
+    }
+}
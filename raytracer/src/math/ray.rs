@@ -6,15 +6,27 @@ use crate::math::Vector3f;
 pub struct Ray {
     pub origin: Vector3f,
     pub direction: Vector3f,
+    /// moment within the camera shutter the ray was sent, moving shapes are
+    /// intersected where they are at this time
+    pub time: f64,
 }
 
 impl Ray {
     pub fn new(origin: Vector3f, direction: Vector3f) -> Self {
-        Self { origin, direction }
+        Self {
+            origin,
+            direction,
+            time: 0.0,
+        }
     }
 
-    /// Get the ray location at time t
-    pub fn at(self: &Self, t: f64) -> Vector3f {
+    pub fn with_time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
+    }
+
+    /// Get the ray location at parameter t along the direction
+    pub fn at(&self, t: f64) -> Vector3f {
         self.origin.add(self.direction * t)
     }
 }
@@ -32,4 +44,13 @@ mod tests {
 
         assert_eq!(actual, Vector3f::new(3.0, 2.0, 3.0))
     }
+
+    #[test]
+    fn time_does_not_move_the_ray() {
+        let ray =
+            Ray::new(Vector3f::new(1.0, 2.0, 3.0), Vector3f::new(1.0, 0.0, 0.0)).with_time(0.5);
+
+        assert_eq!(ray.time, 0.5);
+        assert_eq!(ray.at(2.0), Vector3f::new(3.0, 2.0, 3.0))
+    }
 }
@@ -1,7 +1,33 @@
+use std::cell::RefCell;
+
+use rand::{Rng, SeedableRng, rngs::SmallRng};
+
 use crate::math::Vector3f;
 
+thread_local! {
+    /// Every thread draws from its own generator so renders can be reproduced by
+    /// seeding it before each unit of work, no matter which thread picks it up
+    static RNG: RefCell<SmallRng> = RefCell::new(SmallRng::seed_from_u64(0));
+}
+
+/// reseed the random generator of the current thread
+pub fn seed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = SmallRng::seed_from_u64(seed));
+}
+
 pub fn random_double() -> f64 {
-    rand::random_range(-1.0..=1.0)
+    RNG.with(|rng| rng.borrow_mut().random_range(-1.0..=1.0))
+}
+
+/// random number in [min, max)
+pub fn random_range(min: f64, max: f64) -> f64 {
+    RNG.with(|rng| rng.borrow_mut().random_range(min..max))
+}
+
+/// angle of (x, y) counter clockwise from the x axis as a fraction of a full turn, in [0, 1)
+pub fn turn_fraction(x: f64, y: f64) -> f64 {
+    let turn = y.atan2(x) / (2.0 * std::f64::consts::PI);
+    if turn < 0.0 { turn + 1.0 } else { turn }
 }
 
 const NEAR_ZERO: f64 = 1e-8;
@@ -15,3 +41,24 @@ pub fn is_close_to<T: Into<f64> + Copy>(a: &T, b: &T) -> bool {
     let bb: f64 = (*b).into();
     (aa - bb).abs() < NEAR_ZERO
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_is_reproducible() {
+        seed(42);
+        let a = [random_range(0.0, 1.0), random_range(0.0, 1.0)];
+        seed(42);
+        let b = [random_range(0.0, 1.0), random_range(0.0, 1.0)];
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn turn_fraction_range() {
+        assert_eq!(turn_fraction(1.0, 0.0), 0.0);
+        assert!(is_close_to(&turn_fraction(0.0, 1.0), &0.25));
+        assert!(is_close_to(&turn_fraction(0.0, -1.0), &0.75));
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::ops::{Add, Div, Index, Mul, Neg, Sub};
 
 use crate::math::utils;
 
@@ -45,12 +45,61 @@ impl Vector3f {
         self.x * other.x + self.y * other.y + self.z * other.z
     }
 
+    pub fn cross(&self, other: &Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// reflect this vector around the normal n
+    pub fn reflect(&self, n: &Self) -> Self {
+        *self - *n * (2.0 * self.dot(n))
+    }
+
+    /// refract this unit vector through a surface with normal n, where
+    /// eta_ratio is the ratio of the refraction indices (incident / transmitted)
+    pub fn refract(&self, n: &Self, eta_ratio: f64) -> Self {
+        let cos_theta = (-*self).dot(n).min(1.0);
+        let r_out_perp = (*self + *n * cos_theta) * eta_ratio;
+        let r_out_parallel = *n * -(1.0 - r_out_perp.norm_squared()).abs().sqrt();
+        r_out_perp + r_out_parallel
+    }
+
+    /// Two unit vectors perpendicular to this unit vector and to each other,
+    /// (t, b, self) is right handed. Duff et al. 2017, without branching on the axis
+    pub fn orthonormal_basis(&self) -> (Self, Self) {
+        let sign = 1.0_f64.copysign(self.z);
+        let a = -1.0 / (sign + self.z);
+        let b = self.x * self.y * a;
+        (
+            Self::new(1.0 + sign * self.x * self.x * a, sign * b, -sign * self.x),
+            Self::new(b, sign + self.y * self.y * a, -self.y),
+        )
+    }
+
+    /// random vector with z = 0 and length < 1
+    pub fn random_in_unit_disk() -> Vector3f {
+        loop {
+            let v = Vector3f::new(
+                utils::random_range(-1.0, 1.0),
+                utils::random_range(-1.0, 1.0),
+                0.0,
+            );
+
+            if v.norm_squared() < 1.0 {
+                return v;
+            }
+        }
+    }
+
     pub fn random_unit_vector() -> Vector3f {
         loop {
             let v: Vector3f = Vector3f::new(
-                rand::random_range(-1.0..=1.0),
-                rand::random_range(-1.0..=1.0),
-                rand::random_range(-1.0..=1.0),
+                utils::random_double(),
+                utils::random_double(),
+                utils::random_double(),
             );
 
             let len_squared = v.norm_squared();
@@ -62,6 +111,20 @@ impl Vector3f {
     }
 }
 
+impl Index<usize> for Vector3f {
+    type Output = f64;
+
+    /// component by axis, 0 is x, 1 is y and 2 is z
+    fn index(&self, axis: usize) -> &Self::Output {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("axis {axis} out of range for Vector3f"),
+        }
+    }
+}
+
 impl Add for Vector3f {
     type Output = Self;
 
@@ -154,6 +217,14 @@ mod tests {
         assert_eq!(a.dot(&b), -4.0);
     }
 
+    #[test]
+    fn cross() {
+        let x = Vector3f::new(1.0, 0.0, 0.0);
+        let y = Vector3f::new(0.0, 1.0, 0.0);
+        assert_eq!(x.cross(&y), Vector3f::new(0.0, 0.0, 1.0));
+        assert_eq!(y.cross(&x), Vector3f::new(0.0, 0.0, -1.0));
+    }
+
     #[test]
     fn normalize() {
         let a = Vector3f::new(1.0, 2.0, -3.0);
@@ -168,13 +239,50 @@ mod tests {
     fn equals() {
         let a = Vector3f::new(1.0, 2.0, -3.0);
         assert_eq!(a, a);
-        assert_eq!(a == a, true);
-        assert_eq!(a.eq(&a), true);
+        assert!(a == a);
+        assert!(a.eq(&a));
+    }
+
+    #[test]
+    fn reflect() {
+        let v = Vector3f::new(1.0, -1.0, 0.0);
+        let n = Vector3f::new(0.0, 1.0, 0.0);
+        assert_eq!(v.reflect(&n), Vector3f::new(1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn refract_same_medium() {
+        let v = Vector3f::new(1.0, -1.0, 0.0).normalize();
+        let n = Vector3f::new(0.0, 1.0, 0.0);
+        assert_eq!(v.refract(&n, 1.0), v);
+    }
+
+    #[test]
+    fn orthonormal_basis() {
+        for n in [
+            Vector3f::new(0.0, 0.0, 1.0),
+            Vector3f::new(0.0, 0.0, -1.0),
+            Vector3f::new(1.0, 2.0, -3.0).normalize(),
+        ] {
+            let (t, b) = n.orthonormal_basis();
+            assert!(utils::is_close_to(&t.norm(), &1.0));
+            assert!(utils::is_close_to(&b.norm(), &1.0));
+            assert!(utils::is_close_to(&t.dot(&n), &0.0));
+            assert!(utils::is_close_to(&b.dot(&t), &0.0));
+            assert_eq!(t.cross(&b), n);
+        }
+    }
+
+    #[test]
+    fn random_in_unit_disk() {
+        let a = Vector3f::random_in_unit_disk();
+        assert!(a.norm() < 1.0);
+        assert_eq!(a.z, 0.0);
     }
 
     #[test]
     fn random_unit_vector() {
         let a = Vector3f::random_unit_vector();
-        assert_eq!(utils::is_close_to(&a.norm(), &1.0), true);
+        assert!(utils::is_close_to(&a.norm(), &1.0));
     }
 }
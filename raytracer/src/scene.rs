@@ -0,0 +1,108 @@
+use crate::{
+    geometry::{
+        aabb::Aabb,
+        bvh::Bvh,
+        hittable_list::HittableList,
+        intersectable::{HitRecord, Intersectable},
+    },
+    image::Color,
+    interval::Interval,
+    math::{Point3f, Ray},
+    shapes::shapes::Shapes,
+};
+
+/// Light emitted equally in all directions from a single point
+#[derive(Debug, Clone)]
+pub struct PointLight {
+    pub position: Point3f,
+    /// radiant intensity, falls off with the squared distance
+    pub intensity: Color<f64>,
+}
+
+impl PointLight {
+    pub fn new(position: Point3f, intensity: Color<f64>) -> Self {
+        Self {
+            position,
+            intensity,
+        }
+    }
+}
+
+/// Light coming from far away, seen by rays that hit nothing
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Background {
+    /// white at the horizon fading to blue overhead
+    #[default]
+    Sky,
+    /// the same color in every direction, black leaves only the scene's own
+    /// lights
+    Solid(Color<f64>),
+}
+
+impl Background {
+    pub fn color(&self, ray: &Ray) -> Color<f64> {
+        match self {
+            Background::Sky => {
+                let unit_direction = ray.direction.normalize();
+                let a = 0.5 * (unit_direction.y + 1.0);
+                (Color::new(1.0, 1.0, 1.0) * (1.0 - a)) + (Color::new(0.5, 0.7, 1.0) * a)
+            }
+            Background::Solid(color) => *color,
+        }
+    }
+}
+
+/// Everything that gets rendered, the shapes (which carry their materials),
+/// the lights shining on them and the background behind them
+#[derive(Default)]
+pub struct Scene {
+    objects: HittableList,
+    lights: Vec<PointLight>,
+    background: Background,
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, object: Shapes) {
+        self.objects.add(object);
+    }
+
+    pub fn add_light(&mut self, light: PointLight) {
+        self.lights.push(light);
+    }
+
+    pub fn set_background(&mut self, background: Background) {
+        self.background = background;
+    }
+
+    pub fn background(&self) -> Background {
+        self.background
+    }
+
+    pub fn objects(&self) -> &HittableList {
+        &self.objects
+    }
+
+    pub fn lights(&self) -> &[PointLight] {
+        &self.lights
+    }
+
+    /// Put all shapes under a bounding volume hierarchy, call once the scene is built
+    pub fn build_bvh(&mut self) {
+        let objects = self.objects.take();
+        self.objects.add(Shapes::Bvh(Bvh::new(objects)));
+    }
+}
+
+impl Intersectable for Scene {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        self.objects.intersect(ray, interval)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.objects.bounding_box()
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{
+    geometry::{
+        aabb::Aabb,
+        intersectable::{HitRecord, Intersectable},
+    },
+    interval::Interval,
+    materials::material::Materials,
+    math::{Normal3f, Point3f, Ray, Vector3f, frame::Frame, polynomial, utils},
+    shapes::disk::disk_bounds,
+};
+
+/// Cone from a circular base to an apex, open at the base unless it has a cap.
+/// On the side u is the angle around the axis and v the height, both in
+/// [0, 1], on the cap v is the distance from the axis
+pub struct Cone {
+    base: Point3f,
+    /// local z runs from base to apex
+    frame: Frame,
+    radius: f64,
+    height: f64,
+    capped: bool,
+    material: Materials,
+}
+
+impl Cone {
+    pub fn new(base: Point3f, apex: Point3f, radius: f64, material: Materials) -> Self {
+        let axis = apex - base;
+        Self {
+            base,
+            frame: Frame::from_z(axis),
+            radius,
+            height: axis.norm(),
+            capped: false,
+            material,
+        }
+    }
+
+    /// close the base with a disk
+    pub fn with_cap(mut self) -> Self {
+        self.capped = true;
+        self
+    }
+}
+
+impl Intersectable for Cone {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        let o = self.frame.to_local(&(ray.origin - self.base));
+        let d = self.frame.to_local(&ray.direction);
+
+        let mut closest: Option<(f64, Normal3f, (f64, f64))> = None;
+        let mut consider = |t: f64, normal: Normal3f, uv: (f64, f64)| {
+            if interval.surrounds(t) && closest.is_none_or(|(best, _, _)| t < best) {
+                closest = Some((t, normal, uv));
+            }
+        };
+
+        // x^2 + y^2 = (k (height - z))^2 where k is the slope of the side
+        let k = self.radius / self.height;
+        let k2 = k * k;
+        let w = self.height - o.z;
+        for t in polynomial::solve_quadratic(
+            d.x * d.x + d.y * d.y - k2 * d.z * d.z,
+            2.0 * (o.x * d.x + o.y * d.y + k2 * w * d.z),
+            o.x * o.x + o.y * o.y - k2 * w * w,
+        ) {
+            let p = o + d * t;
+            // the other nappe of the double cone lies above the apex
+            if (0.0..=self.height).contains(&p.z) {
+                let normal = Vector3f::new(p.x, p.y, k2 * (self.height - p.z));
+                let normal = if utils::near_zero_vector(&normal) {
+                    Vector3f::new(0.0, 0.0, 1.0)
+                } else {
+                    normal.normalize()
+                };
+                consider(
+                    t,
+                    normal,
+                    (utils::turn_fraction(p.x, p.y), p.z / self.height),
+                );
+            }
+        }
+
+        if self.capped && d.z != 0.0 {
+            let t = -o.z / d.z;
+            let p = o + d * t;
+            let distance = (p.x * p.x + p.y * p.y).sqrt();
+            if distance <= self.radius {
+                consider(
+                    t,
+                    Vector3f::new(0.0, 0.0, -1.0),
+                    (utils::turn_fraction(p.x, p.y), distance / self.radius),
+                );
+            }
+        }
+
+        let (t, normal, (u, v)) = closest?;
+        Some(
+            HitRecord::new(
+                ray.at(t),
+                self.frame.to_world(&normal),
+                &self.material,
+                t,
+                *ray,
+            )
+            .with_uv(u, v),
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let apex = self.base + self.frame.z * self.height;
+        Aabb::surrounding(
+            &disk_bounds(&self.base, &self.frame.z, self.radius),
+            &Aabb::from_points(apex, apex),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{image::color, materials::lambertian::Lambertian, math::utils::is_close_to};
+
+    /// radius 1 at y = 0, apex at y = 1
+    fn cone() -> Cone {
+        Cone::new(
+            Vector3f::new(0.0, 0.0, 0.0),
+            Vector3f::new(0.0, 1.0, 0.0),
+            1.0,
+            Materials::Lambertian(Lambertian::new(color::RED)),
+        )
+    }
+
+    #[test]
+    fn side() {
+        let cone = cone();
+        let ray = Ray::new(Vector3f::new(5.0, 0.5, 0.0), Vector3f::new(-1.0, 0.0, 0.0));
+        let hit = cone
+            .intersect(&ray, Interval::new(0.0, f64::INFINITY))
+            .unwrap();
+
+        assert!(is_close_to(&hit.time, &4.5));
+        let expected = Vector3f::new(1.0, 1.0, 0.0).normalize();
+        assert_eq!(hit.normal, expected);
+        assert!(is_close_to(&hit.v, &0.5));
+    }
+
+    #[test]
+    fn above_apex_misses() {
+        let cone = cone();
+        let ray = Ray::new(Vector3f::new(5.0, 1.5, 0.0), Vector3f::new(-1.0, 0.0, 0.0));
+        assert!(
+            cone.intersect(&ray, Interval::new(0.0, f64::INFINITY))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn cap() {
+        let interval = Interval::new(0.0, f64::INFINITY);
+        let up = Ray::new(Vector3f::new(0.2, -5.0, 0.0), Vector3f::new(0.0, 1.0, 0.0));
+
+        let open = cone();
+        let hit = open.intersect(&up, interval).unwrap();
+        assert!(!hit.is_front_face);
+
+        let capped = cone().with_cap();
+        let hit = capped.intersect(&up, interval).unwrap();
+        assert!(is_close_to(&hit.time, &5.0));
+        assert_eq!(hit.normal, Vector3f::new(0.0, -1.0, 0.0));
+        assert!(hit.is_front_face);
+    }
+}
@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use crate::{
+    geometry::{
+        aabb::Aabb,
+        intersectable::{HitRecord, Intersectable},
+    },
+    interval::{self, Interval},
+    materials::material::Materials,
+    math::{Ray, Vector3f, utils},
+};
+
+/// Homogeneous volume filling a closed boundary, like smoke or fog. Rays
+/// travel a random free flight distance through it before scattering, so the
+/// denser it is the sooner they scatter. The material is the phase function,
+/// usually isotropic
+pub struct ConstantMedium<T> {
+    /// only its shape is used, the surface itself is not rendered
+    boundary: Arc<T>,
+    /// chance of scattering per unit of distance
+    density: f64,
+    phase: Materials,
+}
+
+impl<T: Intersectable> ConstantMedium<T> {
+    pub fn new(boundary: Arc<T>, density: f64, phase: Materials) -> Self {
+        Self {
+            boundary,
+            density,
+            phase,
+        }
+    }
+}
+
+impl<T: Intersectable> Intersectable for ConstantMedium<T> {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        if self.density <= 0.0 {
+            return None;
+        }
+
+        // where the whole line enters and leaves the boundary, which also
+        // covers rays starting inside
+        let entry = self.boundary.intersect(ray, interval::UNIVERSE)?.time;
+        let exit = self
+            .boundary
+            .intersect(ray, Interval::new(entry + 0.0001, f64::INFINITY))?
+            .time;
+
+        let entry = entry.max(interval.min).max(0.0);
+        let exit = exit.min(interval.max);
+        if entry >= exit {
+            return None;
+        }
+
+        let length = ray.direction.norm();
+        let inside = (exit - entry) * length;
+        // exponentially distributed free flight, 1 - r keeps the log finite
+        let distance = -(1.0 - utils::random_range(0.0, 1.0)).ln() / self.density;
+        if distance > inside {
+            return None;
+        }
+
+        let t = entry + distance / length;
+        // a point inside the volume has no surface, the normal is arbitrary
+        Some(HitRecord {
+            point: ray.at(t),
+            normal: Vector3f::new(1.0, 0.0, 0.0),
+            material: &self.phase,
+            time: t,
+            is_front_face: true,
+            ray: *ray,
+            u: 0.0,
+            v: 0.0,
+            barycentric: None,
+        })
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        image::color,
+        materials::{isotropic::Isotropic, lambertian::Lambertian},
+        shapes::sphere::Sphere,
+    };
+
+    fn fog(density: f64) -> ConstantMedium<Sphere> {
+        ConstantMedium::new(
+            Arc::new(Sphere::new(
+                1.0,
+                Vector3f::new(0.0, 0.0, 0.0),
+                Materials::Lambertian(Lambertian::new(color::RED)),
+            )),
+            density,
+            Materials::Isotropic(Isotropic::new(color::WHITE)),
+        )
+    }
+
+    #[test]
+    fn dense_medium_scatters_near_the_entry() {
+        utils::seed(1);
+        let fog = fog(1e6);
+        let ray = Ray::new(Vector3f::new(0.0, 0.0, 5.0), Vector3f::new(0.0, 0.0, -2.0));
+        let hit = fog
+            .intersect(&ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        assert!((hit.point.z - 1.0).abs() < 1e-3);
+        assert!(matches!(hit.material, Materials::Isotropic(_)));
+    }
+
+    #[test]
+    fn thin_medium_lets_rays_through() {
+        utils::seed(1);
+        let fog = fog(1e-6);
+        let ray = Ray::new(Vector3f::new(0.0, 0.0, 5.0), Vector3f::new(0.0, 0.0, -1.0));
+        assert!(
+            fog.intersect(&ray, Interval::new(0.001, f64::INFINITY))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn transmittance_follows_beer_lambert() {
+        // a diameter of 2 at density 0.5 lets through e^-1 of the rays
+        utils::seed(7);
+        let fog = fog(0.5);
+        let ray = Ray::new(Vector3f::new(0.0, 0.0, 5.0), Vector3f::new(0.0, 0.0, -1.0));
+        let interval = Interval::new(0.001, f64::INFINITY);
+        let passed = (0..10_000)
+            .filter(|_| fog.intersect(&ray, interval).is_none())
+            .count();
+        let expected = 10_000.0 * (-1.0_f64).exp();
+        assert!((passed as f64 - expected).abs() < 150.0, "{passed}");
+    }
+
+    #[test]
+    fn ray_starting_inside() {
+        utils::seed(1);
+        let fog = fog(1e6);
+        let ray = Ray::new(Vector3f::new(0.0, 0.0, 0.5), Vector3f::new(0.0, 0.0, -1.0));
+        let hit = fog
+            .intersect(&ray, Interval::new(0.001, f64::INFINITY))
+            .unwrap();
+        assert!(hit.time < 0.01);
+    }
+}
@@ -0,0 +1,294 @@
+use crate::{
+    geometry::{
+        aabb::{self, Aabb},
+        intersectable::{HitRecord, Intersectable},
+    },
+    interval::Interval,
+    materials::material::Materials,
+    math::{Point3f, Ray, Vector3f, frame::Frame},
+};
+
+/// Where a ray crosses the surface of an axis aligned box
+struct BoxHit {
+    t: f64,
+    /// axis of the face that was hit
+    axis: usize,
+    /// outward normal of that face along the axis, 1 or -1
+    sign: f64,
+}
+
+/// Slab test returning the first crossing of the box surface within the
+/// interval, which is the exit when the ray starts inside
+fn intersect_box(
+    origin: &Point3f,
+    direction: &Vector3f,
+    min: &Point3f,
+    max: &Point3f,
+    interval: Interval,
+) -> Option<BoxHit> {
+    let mut near = (f64::NEG_INFINITY, 0);
+    let mut far = (f64::INFINITY, 0);
+
+    for axis in 0..3 {
+        if direction[axis] == 0.0 {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+
+        let inv_d = 1.0 / direction[axis];
+        let t0 = (min[axis] - origin[axis]) * inv_d;
+        let t1 = (max[axis] - origin[axis]) * inv_d;
+        let (t0, t1) = if inv_d < 0.0 { (t1, t0) } else { (t0, t1) };
+
+        if t0 > near.0 {
+            near = (t0, axis);
+        }
+        if t1 < far.0 {
+            far = (t1, axis);
+        }
+        if far.0 < near.0 {
+            return None;
+        }
+    }
+
+    // the entry face faces against the ray, the exit face along it
+    if interval.surrounds(near.0) {
+        let axis = near.1;
+        Some(BoxHit {
+            t: near.0,
+            axis,
+            sign: -direction[axis].signum(),
+        })
+    } else if interval.surrounds(far.0) {
+        let axis = far.1;
+        Some(BoxHit {
+            t: far.0,
+            axis,
+            sign: direction[axis].signum(),
+        })
+    } else {
+        None
+    }
+}
+
+fn axis_vector(axis: usize, value: f64) -> Vector3f {
+    match axis {
+        0 => Vector3f::new(value, 0.0, 0.0),
+        1 => Vector3f::new(0.0, value, 0.0),
+        _ => Vector3f::new(0.0, 0.0, value),
+    }
+}
+
+/// Texture coordinates on a face, the position across the other two axes
+/// scaled to [0, 1], 0 across an axis the box is flat along
+fn face_uv(point: &Point3f, min: &Point3f, max: &Point3f, axis: usize) -> (f64, f64) {
+    let fraction = |a: usize| {
+        let extent = max[a] - min[a];
+        if extent > 0.0 {
+            (point[a] - min[a]) / extent
+        } else {
+            0.0
+        }
+    };
+    (fraction((axis + 1) % 3), fraction((axis + 2) % 3))
+}
+
+/// Axis aligned box between two opposite corners
+pub struct Cuboid {
+    min: Point3f,
+    max: Point3f,
+    material: Materials,
+}
+
+impl Cuboid {
+    /// a and b are any two opposite corners
+    pub fn new(a: Point3f, b: Point3f, material: Materials) -> Self {
+        Self {
+            min: Vector3f::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Vector3f::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+            material,
+        }
+    }
+}
+
+impl Intersectable for Cuboid {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        let hit = intersect_box(&ray.origin, &ray.direction, &self.min, &self.max, interval)?;
+        let point = ray.at(hit.t);
+        let (u, v) = face_uv(&point, &self.min, &self.max, hit.axis);
+
+        Some(
+            HitRecord::new(
+                point,
+                axis_vector(hit.axis, hit.sign),
+                &self.material,
+                hit.t,
+                *ray,
+            )
+            .with_uv(u, v),
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::from_points(self.min, self.max).padded(aabb::PADDING)
+    }
+}
+
+/// Box with its own orthonormal axes, intersected in its local frame
+pub struct OrientedBox {
+    center: Point3f,
+    half_size: Vector3f,
+    frame: Frame,
+    material: Materials,
+}
+
+impl OrientedBox {
+    /// size is the full extent along the local axes, x_axis is kept as given
+    /// and y_axis is made perpendicular to it
+    pub fn new(
+        center: Point3f,
+        size: Vector3f,
+        x_axis: Vector3f,
+        y_axis: Vector3f,
+        material: Materials,
+    ) -> Self {
+        Self {
+            center,
+            half_size: size / 2.0,
+            frame: Frame::from_xy(x_axis, y_axis),
+            material,
+        }
+    }
+}
+
+impl Intersectable for OrientedBox {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        // a rotation keeps distances, so t is the same in both frames
+        let origin = self.frame.to_local(&(ray.origin - self.center));
+        let direction = self.frame.to_local(&ray.direction);
+        let min = -self.half_size;
+        let hit = intersect_box(&origin, &direction, &min, &self.half_size, interval)?;
+
+        let local_point = origin + direction * hit.t;
+        let (u, v) = face_uv(&local_point, &min, &self.half_size, hit.axis);
+
+        Some(
+            HitRecord::new(
+                ray.at(hit.t),
+                self.frame.to_world(&axis_vector(hit.axis, hit.sign)),
+                &self.material,
+                hit.t,
+                *ray,
+            )
+            .with_uv(u, v),
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let extent = self.frame.world_extent(&self.half_size);
+        Aabb::from_points(self.center - extent, self.center + extent).padded(aabb::PADDING)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{image::color, materials::lambertian::Lambertian, math::utils::is_close_to};
+
+    fn material() -> Materials {
+        Materials::Lambertian(Lambertian::new(color::RED))
+    }
+
+    fn unit_cube() -> Cuboid {
+        Cuboid::new(
+            Vector3f::new(1.0, 1.0, 1.0),
+            Vector3f::new(-1.0, -1.0, -1.0),
+            material(),
+        )
+    }
+
+    #[test]
+    fn hit_from_outside() {
+        let cube = unit_cube();
+        let ray = Ray::new(Vector3f::new(0.5, 0.0, 5.0), Vector3f::new(0.0, 0.0, -1.0));
+        let hit = cube
+            .intersect(&ray, Interval::new(0.0, f64::INFINITY))
+            .unwrap();
+
+        assert!(is_close_to(&hit.time, &4.0));
+        assert_eq!(hit.normal, Vector3f::new(0.0, 0.0, 1.0));
+        assert!(hit.is_front_face);
+        assert!(is_close_to(&hit.u, &0.75));
+        assert!(is_close_to(&hit.v, &0.5));
+    }
+
+    #[test]
+    fn hit_from_inside() {
+        let cube = unit_cube();
+        let ray = Ray::new(Vector3f::new(0.0, 0.0, 0.0), Vector3f::new(1.0, 0.0, 0.0));
+        let hit = cube
+            .intersect(&ray, Interval::new(0.0, f64::INFINITY))
+            .unwrap();
+
+        assert!(is_close_to(&hit.time, &1.0));
+        assert!(!hit.is_front_face);
+        assert_eq!(hit.normal, Vector3f::new(-1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn miss_along_face() {
+        let cube = unit_cube();
+        let ray = Ray::new(Vector3f::new(2.0, 0.0, 5.0), Vector3f::new(0.0, 0.0, -1.0));
+        assert!(
+            cube.intersect(&ray, Interval::new(0.0, f64::INFINITY))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn flat_box() {
+        let slab = Cuboid::new(
+            Vector3f::new(-1.0, 0.0, -1.0),
+            Vector3f::new(1.0, 0.0, 1.0),
+            material(),
+        );
+        let ray = Ray::new(Vector3f::new(0.5, 2.0, 0.0), Vector3f::new(0.0, -1.0, 0.0));
+        let hit = slab
+            .intersect(&ray, Interval::new(0.0, f64::INFINITY))
+            .unwrap();
+        assert!(is_close_to(&hit.time, &2.0));
+        assert!(hit.u.is_finite() && hit.v.is_finite());
+
+        // the side faces span no height
+        let side = face_uv(&Vector3f::new(1.0, 0.0, 0.5), &slab.min, &slab.max, 0);
+        assert_eq!(side, (0.0, 0.75));
+
+        assert!(slab.bounding_box().y.size() > 0.0);
+    }
+
+    #[test]
+    fn oriented_box() {
+        // 2 x 2 x 2 box rotated 45 degrees around y, its corner points at +x
+        let diagonal = Vector3f::new(1.0, 0.0, 1.0);
+        let oriented = OrientedBox::new(
+            Vector3f::new(0.0, 0.0, 0.0),
+            Vector3f::new(2.0, 2.0, 2.0),
+            diagonal,
+            Vector3f::new(0.0, 1.0, 0.0),
+            material(),
+        );
+
+        let ray = Ray::new(Vector3f::new(5.0, 0.0, 0.0), Vector3f::new(-1.0, 0.0, 0.0));
+        let hit = oriented
+            .intersect(&ray, Interval::new(0.0, f64::INFINITY))
+            .unwrap();
+        assert!(is_close_to(&hit.time, &(5.0 - 2.0_f64.sqrt())));
+        assert!(hit.normal.x > 0.0 && is_close_to(&hit.normal.y, &0.0));
+
+        let bbox = oriented.bounding_box();
+        assert!(is_close_to(&bbox.x.max, &2.0_f64.sqrt()));
+        assert!(is_close_to(&bbox.y.max, &1.0));
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{
+    geometry::{
+        aabb::Aabb,
+        intersectable::{HitRecord, Intersectable},
+    },
+    interval::Interval,
+    materials::material::Materials,
+    math::{Normal3f, Point3f, Ray, Vector3f, frame::Frame, polynomial, utils},
+    shapes::disk::disk_bounds,
+};
+
+/// Cylinder between two end points, open unless it has caps. On the side u is
+/// the angle around the axis and v the height, both in [0, 1], on the caps v
+/// is the distance from the axis
+pub struct Cylinder {
+    base: Point3f,
+    /// local z runs from base to top
+    frame: Frame,
+    radius: f64,
+    height: f64,
+    capped: bool,
+    material: Materials,
+}
+
+impl Cylinder {
+    pub fn new(base: Point3f, top: Point3f, radius: f64, material: Materials) -> Self {
+        let axis = top - base;
+        Self {
+            base,
+            frame: Frame::from_z(axis),
+            radius,
+            height: axis.norm(),
+            capped: false,
+            material,
+        }
+    }
+
+    /// close both ends with disks
+    pub fn with_caps(mut self) -> Self {
+        self.capped = true;
+        self
+    }
+}
+
+impl Intersectable for Cylinder {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        let o = self.frame.to_local(&(ray.origin - self.base));
+        let d = self.frame.to_local(&ray.direction);
+
+        let mut closest: Option<(f64, Normal3f, (f64, f64))> = None;
+        let mut consider = |t: f64, normal: Normal3f, uv: (f64, f64)| {
+            if interval.surrounds(t) && closest.is_none_or(|(best, _, _)| t < best) {
+                closest = Some((t, normal, uv));
+            }
+        };
+
+        // x^2 + y^2 = r^2 between z = 0 and z = height
+        for t in polynomial::solve_quadratic(
+            d.x * d.x + d.y * d.y,
+            2.0 * (o.x * d.x + o.y * d.y),
+            o.x * o.x + o.y * o.y - self.radius * self.radius,
+        ) {
+            let p = o + d * t;
+            if (0.0..=self.height).contains(&p.z) {
+                consider(
+                    t,
+                    Vector3f::new(p.x, p.y, 0.0) / self.radius,
+                    (utils::turn_fraction(p.x, p.y), p.z / self.height),
+                );
+            }
+        }
+
+        if self.capped && d.z != 0.0 {
+            for (z, normal) in [(0.0, -1.0), (self.height, 1.0)] {
+                let t = (z - o.z) / d.z;
+                let p = o + d * t;
+                let distance = (p.x * p.x + p.y * p.y).sqrt();
+                if distance <= self.radius {
+                    consider(
+                        t,
+                        Vector3f::new(0.0, 0.0, normal),
+                        (utils::turn_fraction(p.x, p.y), distance / self.radius),
+                    );
+                }
+            }
+        }
+
+        let (t, normal, (u, v)) = closest?;
+        Some(
+            HitRecord::new(
+                ray.at(t),
+                self.frame.to_world(&normal),
+                &self.material,
+                t,
+                *ray,
+            )
+            .with_uv(u, v),
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let top = self.base + self.frame.z * self.height;
+        Aabb::surrounding(
+            &disk_bounds(&self.base, &self.frame.z, self.radius),
+            &disk_bounds(&top, &self.frame.z, self.radius),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{image::color, materials::lambertian::Lambertian, math::utils::is_close_to};
+
+    /// radius 1 from y = 0 to y = 2
+    fn cylinder() -> Cylinder {
+        Cylinder::new(
+            Vector3f::new(0.0, 0.0, 0.0),
+            Vector3f::new(0.0, 2.0, 0.0),
+            1.0,
+            Materials::Lambertian(Lambertian::new(color::RED)),
+        )
+    }
+
+    #[test]
+    fn side() {
+        let cylinder = cylinder();
+        let ray = Ray::new(Vector3f::new(5.0, 1.5, 0.0), Vector3f::new(-1.0, 0.0, 0.0));
+        let hit = cylinder
+            .intersect(&ray, Interval::new(0.0, f64::INFINITY))
+            .unwrap();
+
+        assert!(is_close_to(&hit.time, &4.0));
+        assert_eq!(hit.normal, Vector3f::new(1.0, 0.0, 0.0));
+        assert!(is_close_to(&hit.v, &0.75));
+    }
+
+    #[test]
+    fn open_and_capped_ends() {
+        let interval = Interval::new(0.0, f64::INFINITY);
+        let down = Ray::new(Vector3f::new(0.5, 5.0, 0.0), Vector3f::new(0.0, -1.0, 0.0));
+
+        // straight down through the open ends never touches the side
+        assert!(cylinder().intersect(&down, interval).is_none());
+
+        let capped = cylinder().with_caps();
+        let hit = capped.intersect(&down, interval).unwrap();
+        assert!(is_close_to(&hit.time, &3.0));
+        assert_eq!(hit.normal, Vector3f::new(0.0, 1.0, 0.0));
+        assert!(hit.is_front_face);
+    }
+
+    #[test]
+    fn inside_of_open_cylinder() {
+        let cylinder = cylinder();
+        let ray = Ray::new(Vector3f::new(0.0, 3.0, 0.0), Vector3f::new(1.0, -2.0, 0.0));
+        let hit = cylinder
+            .intersect(&ray, Interval::new(0.0, f64::INFINITY))
+            .unwrap();
+
+        assert!(is_close_to(&hit.point.x, &1.0));
+        assert!(!hit.is_front_face);
+    }
+
+    #[test]
+    fn bounding_box() {
+        let bbox = cylinder().bounding_box();
+        assert!(is_close_to(&bbox.x.min, &-1.0));
+        assert!(is_close_to(&bbox.y.max, &2.0));
+        assert!(is_close_to(&bbox.z.max, &1.0));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{
+    geometry::{
+        aabb::{self, Aabb},
+        intersectable::{HitRecord, Intersectable},
+    },
+    interval::Interval,
+    materials::material::Materials,
+    math::{Normal3f, Point3f, Ray, Vector3f, utils},
+    shapes::plane::intersect_plane,
+};
+
+/// Tight box around a circle with a unit normal, which is flat along the
+/// normal when it is an axis
+pub fn disk_bounds(center: &Point3f, normal: &Normal3f, radius: f64) -> Aabb {
+    // the disk extends radius * sin(angle between the normal and the axis) along each axis
+    let n = normal;
+    let extent = Vector3f::new(
+        (1.0 - n.x * n.x).max(0.0).sqrt(),
+        (1.0 - n.y * n.y).max(0.0).sqrt(),
+        (1.0 - n.z * n.z).max(0.0).sqrt(),
+    ) * radius;
+    Aabb::from_points(*center - extent, *center + extent)
+}
+
+/// Flat circle, u is the angle around the center and v the distance from it,
+/// both scaled to [0, 1]
+pub struct Disk {
+    center: Point3f,
+    normal: Normal3f,
+    radius: f64,
+    /// in plane axes, u = 0 along the first one
+    u_axis: Vector3f,
+    v_axis: Vector3f,
+    material: Materials,
+}
+
+impl Disk {
+    pub fn new(center: Point3f, normal: Normal3f, radius: f64, material: Materials) -> Self {
+        let normal = normal.normalize();
+        let (u_axis, v_axis) = normal.orthonormal_basis();
+        Self {
+            center,
+            normal,
+            radius,
+            u_axis,
+            v_axis,
+            material,
+        }
+    }
+}
+
+impl Intersectable for Disk {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        let t = intersect_plane(ray, &self.center, &self.normal, interval)?;
+        let point = ray.at(t);
+        let offset = point - self.center;
+
+        let distance_squared = offset.norm_squared();
+        if distance_squared > self.radius * self.radius {
+            return None;
+        }
+
+        let u = utils::turn_fraction(offset.dot(&self.u_axis), offset.dot(&self.v_axis));
+        let v = distance_squared.sqrt() / self.radius;
+
+        Some(HitRecord::new(point, self.normal, &self.material, t, *ray).with_uv(u, v))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        disk_bounds(&self.center, &self.normal, self.radius).padded(aabb::PADDING)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{image::color, materials::lambertian::Lambertian, math::utils::is_close_to};
+
+    fn disk() -> Disk {
+        Disk::new(
+            Vector3f::new(0.0, 0.0, -2.0),
+            Vector3f::new(0.0, 0.0, 1.0),
+            1.0,
+            Materials::Lambertian(Lambertian::new(color::RED)),
+        )
+    }
+
+    #[test]
+    fn inside_and_outside() {
+        let disk = disk();
+        let interval = Interval::new(0.0, f64::INFINITY);
+        let forward = Vector3f::new(0.0, 0.0, -1.0);
+
+        let hit = disk
+            .intersect(&Ray::new(Vector3f::new(0.5, 0.0, 0.0), forward), interval)
+            .unwrap();
+        assert!(is_close_to(&hit.time, &2.0));
+        assert!(is_close_to(&hit.v, &0.5));
+
+        assert!(
+            disk.intersect(&Ray::new(Vector3f::new(0.8, 0.8, 0.0), forward), interval)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn bounding_box_is_tight() {
+        let bbox = disk().bounding_box();
+        assert!(is_close_to(&bbox.x.min, &-1.0));
+        assert!(is_close_to(&bbox.y.max, &1.0));
+        assert!(bbox.z.size() < 1e-3);
+    }
+}
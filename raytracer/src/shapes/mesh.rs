@@ -0,0 +1,219 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    geometry::{
+        aabb::{self, Aabb},
+        bvh::Bvh,
+        intersectable::{HitRecord, Intersectable},
+    },
+    interval::Interval,
+    materials::material::Materials,
+    math::{Normal3f, Point3f, Ray},
+    shapes::triangle::{self, intersect_triangle},
+};
+
+/// Triangle of a mesh, as indices into the mesh arrays
+#[derive(Debug, Clone, PartialEq)]
+pub struct Face {
+    pub positions: [usize; 3],
+    pub normals: Option<[usize; 3]>,
+    pub uvs: Option<[usize; 3]>,
+    /// index into the mesh materials
+    pub material: usize,
+}
+
+/// Named range of faces, like OBJ groups
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    pub name: String,
+    pub faces: Range<usize>,
+}
+
+/// Vertex data shared by all the triangles of a mesh
+#[derive(Debug, Default)]
+pub struct MeshData {
+    pub positions: Vec<Point3f>,
+    pub normals: Vec<Normal3f>,
+    pub uvs: Vec<(f64, f64)>,
+    pub faces: Vec<Face>,
+    pub materials: Vec<Materials>,
+    pub groups: Vec<Group>,
+}
+
+impl MeshData {
+    /// Check every face only references existing vertices and materials
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, face) in self.faces.iter().enumerate() {
+            let in_range = |indices: &[usize; 3], len: usize| indices.iter().all(|&j| j < len);
+
+            if !in_range(&face.positions, self.positions.len())
+                || face
+                    .normals
+                    .is_some_and(|n| !in_range(&n, self.normals.len()))
+                || face.uvs.is_some_and(|uv| !in_range(&uv, self.uvs.len()))
+                || face.material >= self.materials.len()
+            {
+                return Err(format!("face {i} references data the mesh does not have"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Use a single material for every face
+    pub fn replace_materials(&mut self, material: Materials) {
+        self.materials = vec![material];
+        for face in &mut self.faces {
+            face.material = 0;
+        }
+    }
+
+    fn vertices(&self, face: &Face) -> [Point3f; 3] {
+        face.positions.map(|i| self.positions[i])
+    }
+}
+
+/// One face of a mesh as seen by the bounding volume hierarchy
+struct MeshTriangle {
+    data: Arc<MeshData>,
+    face: usize,
+}
+
+impl Intersectable for MeshTriangle {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        let face = &self.data.faces[self.face];
+        let vertices = self.data.vertices(face);
+        let hit = intersect_triangle(ray, &vertices, interval)?;
+
+        Some(triangle::triangle_hit(
+            ray,
+            hit,
+            &vertices,
+            face.normals.map(|n| n.map(|i| self.data.normals[i])),
+            face.uvs.map(|uv| uv.map(|i| self.data.uvs[i])),
+            &self.data.materials[face.material],
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let [a, b, c] = self.data.vertices(&self.data.faces[self.face]);
+        Aabb::surrounding(&Aabb::from_points(a, b), &Aabb::from_points(c, c)).padded(aabb::PADDING)
+    }
+}
+
+/// Indexed triangle mesh, faces share vertices and materials
+pub struct Mesh {
+    data: Arc<MeshData>,
+    bvh: Bvh<MeshTriangle>,
+}
+
+impl Mesh {
+    /// panics if a face references data the mesh does not have, see [`MeshData::validate`]
+    pub fn new(data: MeshData) -> Self {
+        if let Err(e) = data.validate() {
+            panic!("invalid mesh: {e}");
+        }
+
+        let data = Arc::new(data);
+        let triangles = (0..data.faces.len())
+            .map(|face| MeshTriangle {
+                data: Arc::clone(&data),
+                face,
+            })
+            .collect();
+
+        Self {
+            bvh: Bvh::new(triangles),
+            data,
+        }
+    }
+
+    pub fn data(&self) -> &MeshData {
+        &self.data
+    }
+}
+
+impl Intersectable for Mesh {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        self.bvh.intersect(ray, interval)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        image::color,
+        materials::lambertian::Lambertian,
+        math::{Vector3f, utils::is_close_to},
+    };
+
+    /// unit square in the xy plane split into two triangles with different materials
+    fn square() -> MeshData {
+        MeshData {
+            positions: vec![
+                Vector3f::new(0.0, 0.0, 0.0),
+                Vector3f::new(1.0, 0.0, 0.0),
+                Vector3f::new(1.0, 1.0, 0.0),
+                Vector3f::new(0.0, 1.0, 0.0),
+            ],
+            faces: vec![
+                Face {
+                    positions: [0, 1, 2],
+                    normals: None,
+                    uvs: None,
+                    material: 0,
+                },
+                Face {
+                    positions: [0, 2, 3],
+                    normals: None,
+                    uvs: None,
+                    material: 1,
+                },
+            ],
+            materials: vec![
+                Materials::Lambertian(Lambertian::new(color::RED)),
+                Materials::Lambertian(Lambertian::new(color::GREEN)),
+            ],
+            ..MeshData::default()
+        }
+    }
+
+    #[test]
+    fn hits_each_face_with_its_material() {
+        let mesh = Mesh::new(square());
+        let interval = Interval::new(0.0, f64::INFINITY);
+        let down = Vector3f::new(0.0, 0.0, -1.0);
+
+        let hit = mesh
+            .intersect(&Ray::new(Vector3f::new(0.75, 0.25, 1.0), down), interval)
+            .unwrap();
+        assert!(is_close_to(&hit.time, &1.0));
+        assert!(matches!(hit.material, Materials::Lambertian(_)));
+        assert!(std::ptr::eq(hit.material, &mesh.data().materials[0]));
+
+        let hit = mesh
+            .intersect(&Ray::new(Vector3f::new(0.25, 0.75, 1.0), down), interval)
+            .unwrap();
+        assert!(std::ptr::eq(hit.material, &mesh.data().materials[1]));
+
+        assert!(
+            mesh.intersect(&Ray::new(Vector3f::new(1.5, 0.5, 1.0), down), interval)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn validate_rejects_bad_index() {
+        let mut data = square();
+        data.faces[1].positions[2] = 4;
+        assert!(data.validate().is_err());
+
+        let mut data = square();
+        data.faces[0].material = 2;
+        assert!(data.validate().is_err());
+    }
+}
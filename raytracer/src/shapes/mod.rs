@@ -1,2 +1,14 @@
+pub mod cone;
+pub mod constant_medium;
+pub mod cuboid;
+pub mod cylinder;
+pub mod disk;
+pub mod mesh;
+pub mod plane;
+pub mod quad;
+#[allow(clippy::module_inception)]
 pub mod shapes;
 pub mod sphere;
+pub mod torus;
+pub mod transformed;
+pub mod triangle;
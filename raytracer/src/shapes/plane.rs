@@ -0,0 +1,131 @@
+use crate::{
+    geometry::{
+        aabb::{self, Aabb},
+        intersectable::{HitRecord, Intersectable},
+    },
+    interval::Interval,
+    materials::material::Materials,
+    math::{Normal3f, Point3f, Ray, Vector3f},
+};
+
+/// Denominators smaller than this mean the ray is parallel to the plane
+const PARALLEL_EPSILON: f64 = 1e-12;
+
+/// Ray parameter where the ray crosses the plane through point with the given
+/// normal, if it does so within the interval
+pub fn intersect_plane(
+    ray: &Ray,
+    point: &Point3f,
+    normal: &Normal3f,
+    interval: Interval,
+) -> Option<f64> {
+    let denominator = normal.dot(&ray.direction);
+    if denominator.abs() < PARALLEL_EPSILON {
+        return None;
+    }
+
+    let t = normal.dot(&(*point - ray.origin)) / denominator;
+    interval.surrounds(t).then_some(t)
+}
+
+/// Infinite plane, texture coordinates are distances along two axes in the
+/// plane so textures repeat every unit
+pub struct Plane {
+    point: Point3f,
+    normal: Normal3f,
+    /// in plane axes for the texture coordinates
+    u_axis: Vector3f,
+    v_axis: Vector3f,
+    material: Materials,
+}
+
+impl Plane {
+    pub fn new(point: Point3f, normal: Normal3f, material: Materials) -> Self {
+        let normal = normal.normalize();
+        let (u_axis, v_axis) = normal.orthonormal_basis();
+        Self {
+            point,
+            normal,
+            u_axis,
+            v_axis,
+            material,
+        }
+    }
+}
+
+impl Intersectable for Plane {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        let t = intersect_plane(ray, &self.point, &self.normal, interval)?;
+        let point = ray.at(t);
+        let offset = point - self.point;
+
+        Some(
+            HitRecord::new(point, self.normal, &self.material, t, *ray)
+                .with_uv(offset.dot(&self.u_axis), offset.dot(&self.v_axis)),
+        )
+    }
+
+    /// unbounded, the bounding volume hierarchy tests it separately
+    fn bounding_box(&self) -> Aabb {
+        aabb::UNIVERSE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{image::color, materials::lambertian::Lambertian, math::utils::is_close_to};
+
+    fn ground() -> Plane {
+        Plane::new(
+            Vector3f::new(0.0, -1.0, 0.0),
+            Vector3f::new(0.0, 2.0, 0.0),
+            Materials::Lambertian(Lambertian::new(color::GREEN)),
+        )
+    }
+
+    #[test]
+    fn hit_from_above_and_below() {
+        let plane = ground();
+        let interval = Interval::new(0.0, f64::INFINITY);
+
+        let ray = Ray::new(Vector3f::new(3.0, 1.0, 0.0), Vector3f::new(0.0, -1.0, 0.0));
+        let hit = plane.intersect(&ray, interval).unwrap();
+        assert!(is_close_to(&hit.time, &2.0));
+        assert!(hit.is_front_face);
+        assert_eq!(hit.normal, Vector3f::new(0.0, 1.0, 0.0));
+
+        let ray = Ray::new(Vector3f::new(0.0, -3.0, 0.0), Vector3f::new(0.0, 1.0, 0.0));
+        let hit = plane.intersect(&ray, interval).unwrap();
+        assert!(!hit.is_front_face);
+        assert_eq!(hit.normal, Vector3f::new(0.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn parallel_and_behind() {
+        let plane = ground();
+        let interval = Interval::new(0.0, f64::INFINITY);
+
+        let parallel = Ray::new(Vector3f::new(0.0, 1.0, 0.0), Vector3f::new(1.0, 0.0, 0.0));
+        assert!(plane.intersect(&parallel, interval).is_none());
+
+        let away = Ray::new(Vector3f::new(0.0, 1.0, 0.0), Vector3f::new(0.0, 1.0, 0.0));
+        assert!(plane.intersect(&away, interval).is_none());
+    }
+
+    #[test]
+    fn uv_is_distance_in_plane() {
+        let plane = ground();
+        let interval = Interval::new(0.0, f64::INFINITY);
+        let down = Vector3f::new(0.0, -1.0, 0.0);
+
+        let a = plane
+            .intersect(&Ray::new(Vector3f::new(0.0, 0.0, 0.0), down), interval)
+            .unwrap();
+        let b = plane
+            .intersect(&Ray::new(Vector3f::new(3.0, 0.0, 4.0), down), interval)
+            .unwrap();
+        let distance = ((a.u - b.u).powi(2) + (a.v - b.v).powi(2)).sqrt();
+        assert!(is_close_to(&distance, &5.0));
+    }
+}
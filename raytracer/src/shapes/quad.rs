@@ -0,0 +1,115 @@
+use crate::{
+    geometry::{
+        aabb::{self, Aabb},
+        intersectable::{HitRecord, Intersectable},
+    },
+    interval::{Interval, UNIT},
+    materials::material::Materials,
+    math::{Normal3f, Point3f, Ray, Vector3f},
+    shapes::plane::intersect_plane,
+};
+
+/// Parallelogram with a corner and two edges, the texture coordinates are the
+/// fractions along the edges so they span [0, 1]
+pub struct Quad {
+    corner: Point3f,
+    u: Vector3f,
+    v: Vector3f,
+    normal: Normal3f,
+    /// n / (n . n) with n = u x v, projects a point onto the edges
+    w: Vector3f,
+    material: Materials,
+}
+
+impl Quad {
+    /// the normal is u x v, so the front face is the one the edges turn counter clockwise on
+    pub fn new(corner: Point3f, u: Vector3f, v: Vector3f, material: Materials) -> Self {
+        let n = u.cross(&v);
+        Self {
+            corner,
+            u,
+            v,
+            normal: n.normalize(),
+            w: n / n.norm_squared(),
+            material,
+        }
+    }
+}
+
+impl Intersectable for Quad {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        let t = intersect_plane(ray, &self.corner, &self.normal, interval)?;
+        let point = ray.at(t);
+        let offset = point - self.corner;
+
+        let alpha = self.w.dot(&offset.cross(&self.v));
+        let beta = self.w.dot(&self.u.cross(&offset));
+        if !UNIT.contains(alpha) || !UNIT.contains(beta) {
+            return None;
+        }
+
+        Some(HitRecord::new(point, self.normal, &self.material, t, *ray).with_uv(alpha, beta))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let diagonal1 = Aabb::from_points(self.corner, self.corner + self.u + self.v);
+        let diagonal2 = Aabb::from_points(self.corner + self.u, self.corner + self.v);
+        Aabb::surrounding(&diagonal1, &diagonal2).padded(aabb::PADDING)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{image::color, materials::lambertian::Lambertian, math::utils::is_close_to};
+
+    /// 2 by 1 rectangle in the z = -1 plane facing +z
+    fn quad() -> Quad {
+        Quad::new(
+            Vector3f::new(-1.0, 0.0, -1.0),
+            Vector3f::new(2.0, 0.0, 0.0),
+            Vector3f::new(0.0, 1.0, 0.0),
+            Materials::Lambertian(Lambertian::new(color::RED)),
+        )
+    }
+
+    #[test]
+    fn uv_spans_edges() {
+        let quad = quad();
+        let interval = Interval::new(0.0, f64::INFINITY);
+        let ray = Ray::new(Vector3f::new(0.5, 0.25, 0.0), Vector3f::new(0.0, 0.0, -1.0));
+
+        let hit = quad.intersect(&ray, interval).unwrap();
+        assert!(is_close_to(&hit.time, &1.0));
+        assert!(is_close_to(&hit.u, &0.75));
+        assert!(is_close_to(&hit.v, &0.25));
+        assert!(hit.is_front_face);
+    }
+
+    #[test]
+    fn misses_outside_edges() {
+        let quad = quad();
+        let interval = Interval::new(0.0, f64::INFINITY);
+        for (x, y) in [(-1.5, 0.5), (1.5, 0.5), (0.0, -0.1), (0.0, 1.1)] {
+            let ray = Ray::new(Vector3f::new(x, y, 0.0), Vector3f::new(0.0, 0.0, -1.0));
+            assert!(quad.intersect(&ray, interval).is_none());
+        }
+    }
+
+    #[test]
+    fn parallelogram() {
+        let quad = Quad::new(
+            Vector3f::new(0.0, 0.0, 0.0),
+            Vector3f::new(1.0, 0.0, 0.0),
+            Vector3f::new(1.0, 1.0, 0.0),
+            Materials::Lambertian(Lambertian::new(color::RED)),
+        );
+        let interval = Interval::new(0.0, f64::INFINITY);
+        let down = Vector3f::new(0.0, 0.0, -1.0);
+
+        let inside = Ray::new(Vector3f::new(1.5, 0.9, 1.0), down);
+        assert!(quad.intersect(&inside, interval).is_some());
+        let outside = Ray::new(Vector3f::new(0.2, 0.9, 1.0), down);
+        assert!(quad.intersect(&outside, interval).is_none());
+    }
+}
@@ -1,8 +1,35 @@
-use crate::geometry::intersectable::Intersectable;
+use crate::geometry::{
+    aabb::Aabb, bvh::Bvh, hittable_list::HittableList, intersectable::Intersectable,
+};
+pub use crate::shapes::cone::Cone;
+pub use crate::shapes::constant_medium::ConstantMedium;
+pub use crate::shapes::cuboid::{Cuboid, OrientedBox};
+pub use crate::shapes::cylinder::Cylinder;
+pub use crate::shapes::disk::Disk;
+pub use crate::shapes::mesh::Mesh;
+pub use crate::shapes::plane::Plane;
+pub use crate::shapes::quad::Quad;
 pub use crate::shapes::sphere::Sphere;
+pub use crate::shapes::torus::Torus;
+pub use crate::shapes::transformed::Transformed;
+pub use crate::shapes::triangle::Triangle;
 
 pub enum Shapes {
     Sphere(Sphere),
+    Triangle(Triangle),
+    Mesh(Mesh),
+    Plane(Plane),
+    Disk(Disk),
+    Quad(Quad),
+    Cuboid(Cuboid),
+    OrientedBox(OrientedBox),
+    Cylinder(Cylinder),
+    Cone(Cone),
+    Torus(Torus),
+    Transformed(Transformed<Shapes>),
+    ConstantMedium(ConstantMedium<Shapes>),
+    Bvh(Bvh<Shapes>),
+    List(HittableList),
 }
 
 impl Intersectable for Shapes {
@@ -10,9 +37,43 @@ impl Intersectable for Shapes {
         &self,
         ray: &crate::math::Ray,
         interval: crate::interval::Interval,
-    ) -> Option<crate::geometry::intersectable::HitRecord> {
+    ) -> Option<crate::geometry::intersectable::HitRecord<'_>> {
         match self {
             Shapes::Sphere(s) => s.intersect(ray, interval),
+            Shapes::Triangle(t) => t.intersect(ray, interval),
+            Shapes::Mesh(m) => m.intersect(ray, interval),
+            Shapes::Plane(p) => p.intersect(ray, interval),
+            Shapes::Disk(d) => d.intersect(ray, interval),
+            Shapes::Quad(q) => q.intersect(ray, interval),
+            Shapes::Cuboid(c) => c.intersect(ray, interval),
+            Shapes::OrientedBox(b) => b.intersect(ray, interval),
+            Shapes::Cylinder(c) => c.intersect(ray, interval),
+            Shapes::Cone(c) => c.intersect(ray, interval),
+            Shapes::Torus(t) => t.intersect(ray, interval),
+            Shapes::Transformed(t) => t.intersect(ray, interval),
+            Shapes::ConstantMedium(m) => m.intersect(ray, interval),
+            Shapes::Bvh(b) => b.intersect(ray, interval),
+            Shapes::List(l) => l.intersect(ray, interval),
+        }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        match self {
+            Shapes::Sphere(s) => s.bounding_box(),
+            Shapes::Triangle(t) => t.bounding_box(),
+            Shapes::Mesh(m) => m.bounding_box(),
+            Shapes::Plane(p) => p.bounding_box(),
+            Shapes::Disk(d) => d.bounding_box(),
+            Shapes::Quad(q) => q.bounding_box(),
+            Shapes::Cuboid(c) => c.bounding_box(),
+            Shapes::OrientedBox(b) => b.bounding_box(),
+            Shapes::Cylinder(c) => c.bounding_box(),
+            Shapes::Cone(c) => c.bounding_box(),
+            Shapes::Torus(t) => t.bounding_box(),
+            Shapes::Transformed(t) => t.bounding_box(),
+            Shapes::ConstantMedium(m) => m.bounding_box(),
+            Shapes::Bvh(b) => b.bounding_box(),
+            Shapes::List(l) => l.bounding_box(),
         }
     }
 }
@@ -1,23 +1,54 @@
 use crate::{
-    geometry::intersectable::{HitRecord, Intersectable},
-    image::color::Colorf64,
-    math::Vector3f,
+    geometry::{
+        aabb::Aabb,
+        intersectable::{HitRecord, Intersectable},
+    },
+    materials::material::Materials,
+    math::{Vector3f, utils},
 };
 
+/// u is the angle around the y axis starting from -x, v runs from the bottom
+/// pole to the top one, both in [0, 1]
 pub struct Sphere {
     radius: f64,
+    /// center at time 0
     position: Vector3f,
-    color: Colorf64,
+    /// how far the center travels from time 0 to time 1
+    motion: Vector3f,
+    material: Materials,
 }
 
 impl Sphere {
-    pub fn new(radius: f64, position: Vector3f, color: Colorf64) -> Self {
+    pub fn new(radius: f64, position: Vector3f, material: Materials) -> Self {
         Self {
             radius,
             position,
-            color,
+            motion: Vector3f::new(0.0, 0.0, 0.0),
+            material,
         }
     }
+
+    /// Sphere moving in a straight line from start at time 0 to end at time 1,
+    /// it rests at the closest keyframe outside of that
+    pub fn moving(radius: f64, start: Vector3f, end: Vector3f, material: Materials) -> Self {
+        Self {
+            radius,
+            position: start,
+            motion: end - start,
+            material,
+        }
+    }
+
+    fn center(&self, time: f64) -> Vector3f {
+        self.position + self.motion * time.clamp(0.0, 1.0)
+    }
+}
+
+/// surface coordinates of a point on the unit sphere
+fn sphere_uv(p: &Vector3f) -> (f64, f64) {
+    let u = utils::turn_fraction(-p.x, p.z);
+    let v = (-p.y).clamp(-1.0, 1.0).acos() / std::f64::consts::PI;
+    (u, v)
 }
 
 impl Intersectable for Sphere {
@@ -25,8 +56,9 @@ impl Intersectable for Sphere {
         &self,
         ray: &crate::math::Ray,
         interval: crate::interval::Interval,
-    ) -> Option<crate::geometry::intersectable::HitRecord> {
-        let oc = self.position - ray.origin;
+    ) -> Option<crate::geometry::intersectable::HitRecord<'_>> {
+        let center = self.center(ray.time);
+        let oc = center - ray.origin;
         let a = ray.direction.norm_squared();
         let h = ray.direction.dot(&oc);
         let c = oc.norm_squared() - self.radius * self.radius;
@@ -44,17 +76,77 @@ impl Intersectable for Sphere {
             }
 
             let hit_point = ray.at(root);
-            let normal = (hit_point - self.position) / self.radius;
-
-            Some(HitRecord::new(
-                hit_point,
-                normal,
-                self.color.clone() * 0.5 + normal,
-                root,
-                ray.clone().into(),
-            ))
+            let normal = (hit_point - center) / self.radius;
+            let (u, v) = sphere_uv(&normal);
+
+            Some(HitRecord::new(hit_point, normal, &self.material, root, *ray).with_uv(u, v))
         } else {
             None
         }
     }
+
+    fn bounding_box(&self) -> Aabb {
+        // covers the sphere along its whole motion
+        let r = Vector3f::new(self.radius, self.radius, self.radius);
+        let end = self.position + self.motion;
+        Aabb::surrounding(
+            &Aabb::from_points(self.position - r, self.position + r),
+            &Aabb::from_points(end - r, end + r),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        image::color, interval::Interval, materials::lambertian::Lambertian, math::Ray,
+        math::utils::is_close_to,
+    };
+
+    fn moving() -> Sphere {
+        Sphere::moving(
+            1.0,
+            Vector3f::new(0.0, 0.0, 0.0),
+            Vector3f::new(4.0, 0.0, 0.0),
+            Materials::Lambertian(Lambertian::new(color::RED)),
+        )
+    }
+
+    #[test]
+    fn moving_sphere_follows_ray_time() {
+        let sphere = moving();
+        let interval = Interval::new(0.0, f64::INFINITY);
+        let down = Vector3f::new(0.0, -1.0, 0.0);
+        let ray = Ray::new(Vector3f::new(2.0, 5.0, 0.0), down);
+
+        // halfway through the motion the center is at x = 2
+        let hit = sphere.intersect(&ray.with_time(0.5), interval).unwrap();
+        assert!(is_close_to(&hit.time, &4.0));
+        assert_eq!(hit.normal, Vector3f::new(0.0, 1.0, 0.0));
+        assert!(sphere.intersect(&ray, interval).is_none());
+
+        // past the last keyframe it stays at the end
+        let ray = Ray::new(Vector3f::new(4.0, 5.0, 0.0), down);
+        assert!(sphere.intersect(&ray.with_time(3.0), interval).is_some());
+    }
+
+    #[test]
+    fn uv() {
+        let at = |x, y, z| sphere_uv(&Vector3f::new(x, y, z));
+        assert_eq!(at(-1.0, 0.0, 0.0), (0.0, 0.5));
+        assert_eq!(at(0.0, 0.0, 1.0), (0.25, 0.5));
+        assert_eq!(at(1.0, 0.0, 0.0), (0.5, 0.5));
+        assert!(is_close_to(&at(0.0, 0.0, -1.0).0, &0.75));
+        assert_eq!(at(0.0, -1.0, 0.0).1, 0.0);
+        assert_eq!(at(0.0, 1.0, 0.0).1, 1.0);
+    }
+
+    #[test]
+    fn moving_bounding_box() {
+        let bbox = moving().bounding_box();
+        assert!(is_close_to(&bbox.x.min, &-1.0));
+        assert!(is_close_to(&bbox.x.max, &5.0));
+        assert!(is_close_to(&bbox.y.max, &1.0));
+    }
 }
@@ -0,0 +1,162 @@
+use crate::{
+    geometry::{
+        aabb::Aabb,
+        intersectable::{HitRecord, Intersectable},
+    },
+    interval::Interval,
+    materials::material::Materials,
+    math::{Point3f, Ray, Vector3f, frame::Frame, polynomial, utils},
+    shapes::disk::disk_bounds,
+};
+
+/// Ring around an axis, the tube of minor_radius is swept along a circle of
+/// major_radius. u is the angle around the axis and v the angle around the tube
+pub struct Torus {
+    center: Point3f,
+    /// local z is the axis
+    frame: Frame,
+    major_radius: f64,
+    minor_radius: f64,
+    material: Materials,
+}
+
+impl Torus {
+    pub fn new(
+        center: Point3f,
+        axis: Vector3f,
+        major_radius: f64,
+        minor_radius: f64,
+        material: Materials,
+    ) -> Self {
+        Self {
+            center,
+            frame: Frame::from_z(axis),
+            major_radius,
+            minor_radius,
+            material,
+        }
+    }
+}
+
+impl Intersectable for Torus {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        // solve for the distance s along a unit direction, t = s / |direction|,
+        // which keeps the quartic coefficients well scaled
+        let length = ray.direction.norm();
+        let o = self.frame.to_local(&(ray.origin - self.center));
+        let d = self.frame.to_local(&ray.direction) / length;
+
+        // (|p|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + y^2)
+        let r2 = self.major_radius * self.major_radius;
+        let f = o.dot(&d);
+        let g = o.norm_squared() + r2 - self.minor_radius * self.minor_radius;
+        let roots = polynomial::solve_quartic(
+            1.0,
+            4.0 * f,
+            4.0 * f * f + 2.0 * g - 4.0 * r2 * (d.x * d.x + d.y * d.y),
+            4.0 * f * g - 8.0 * r2 * (o.x * d.x + o.y * d.y),
+            g * g - 4.0 * r2 * (o.x * o.x + o.y * o.y),
+        );
+        let t = roots
+            .into_iter()
+            .map(|s| s / length)
+            .find(|&t| interval.surrounds(t))?;
+
+        // the normal points away from the closest point on the center circle
+        let p = o + d * (t * length);
+        let ring = Vector3f::new(p.x, p.y, 0.0);
+        let ring = if utils::near_zero_vector(&ring) {
+            ring
+        } else {
+            ring.normalize() * self.major_radius
+        };
+        let normal = (p - ring) / self.minor_radius;
+
+        let u = utils::turn_fraction(p.x, p.y);
+        let v = utils::turn_fraction((p.x * p.x + p.y * p.y).sqrt() - self.major_radius, p.z);
+
+        Some(
+            HitRecord::new(
+                ray.at(t),
+                self.frame.to_world(&normal),
+                &self.material,
+                t,
+                *ray,
+            )
+            .with_uv(u, v),
+        )
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let ring = disk_bounds(&self.center, &self.frame.z, self.major_radius);
+        let tube = 2.0 * self.minor_radius;
+        Aabb::new(
+            ring.x.expand(tube),
+            ring.y.expand(tube),
+            ring.z.expand(tube),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{image::color, materials::lambertian::Lambertian, math::utils::is_close_to};
+
+    /// lying in the xz plane, the tube spans 0.75 to 1.25 from the center
+    fn torus() -> Torus {
+        Torus::new(
+            Vector3f::new(0.0, 0.0, 0.0),
+            Vector3f::new(0.0, 1.0, 0.0),
+            1.0,
+            0.25,
+            Materials::Lambertian(Lambertian::new(color::RED)),
+        )
+    }
+
+    #[test]
+    fn through_the_ring() {
+        let torus = torus();
+        let interval = Interval::new(0.0, f64::INFINITY);
+        let ray = Ray::new(Vector3f::new(5.0, 0.0, 0.0), Vector3f::new(-2.0, 0.0, 0.0));
+
+        let hit = torus.intersect(&ray, interval).unwrap();
+        assert!(is_close_to(&hit.time, &1.875));
+        assert_eq!(hit.normal, Vector3f::new(1.0, 0.0, 0.0));
+
+        // from the hole the next hit is the inner side of the tube
+        let ray = Ray::new(Vector3f::new(0.0, 0.0, 0.0), Vector3f::new(-1.0, 0.0, 0.0));
+        let hit = torus.intersect(&ray, interval).unwrap();
+        assert!(is_close_to(&hit.time, &0.75));
+        assert!(hit.is_front_face);
+    }
+
+    #[test]
+    fn misses_through_the_hole() {
+        let torus = torus();
+        let ray = Ray::new(Vector3f::new(0.0, 5.0, 0.0), Vector3f::new(0.0, -1.0, 0.0));
+        assert!(
+            torus
+                .intersect(&ray, Interval::new(0.0, f64::INFINITY))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn top_of_the_tube() {
+        let torus = torus();
+        let ray = Ray::new(Vector3f::new(0.0, 5.0, -1.0), Vector3f::new(0.0, -1.0, 0.0));
+        let hit = torus
+            .intersect(&ray, Interval::new(0.0, f64::INFINITY))
+            .unwrap();
+        assert!(is_close_to(&hit.time, &4.75));
+        assert_eq!(hit.normal, Vector3f::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn bounding_box() {
+        let bbox = torus().bounding_box();
+        assert!(is_close_to(&bbox.x.max, &1.25));
+        assert!(is_close_to(&bbox.y.min, &-0.25));
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use crate::{
+    geometry::{
+        aabb::{self, Aabb},
+        intersectable::{HitRecord, Intersectable},
+    },
+    interval::Interval,
+    math::{Point3f, Ray, Vector3f, matrix4::Transform},
+};
+
+/// Shape placed in the world by a transform. The shape is shared, so many
+/// instances of the same geometry only cost a transform each
+pub struct Transformed<T> {
+    object: Arc<T>,
+    transform: Transform,
+    bbox: Aabb,
+}
+
+impl<T: Intersectable> Transformed<T> {
+    pub fn new(object: Arc<T>, transform: Transform) -> Self {
+        let bbox = transform_box(&object.bounding_box(), &transform);
+        Self {
+            object,
+            transform,
+            bbox,
+        }
+    }
+}
+
+/// Box around the eight transformed corners of bbox
+fn transform_box(bbox: &Aabb, transform: &Transform) -> Aabb {
+    if !bbox.is_finite() {
+        return aabb::UNIVERSE;
+    }
+
+    let mut result = aabb::EMPTY;
+    for x in [bbox.x.min, bbox.x.max] {
+        for y in [bbox.y.min, bbox.y.max] {
+            for z in [bbox.z.min, bbox.z.max] {
+                let corner: Point3f = transform.point(&Vector3f::new(x, y, z));
+                result = Aabb::surrounding(&result, &Aabb::from_points(corner, corner));
+            }
+        }
+    }
+    result
+}
+
+impl<T: Intersectable> Intersectable for Transformed<T> {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        // the direction is not normalized so t is the same in both spaces
+        let local_ray = Ray::new(
+            self.transform.inverse.transform_point(&ray.origin),
+            self.transform.inverse.transform_vector(&ray.direction),
+        )
+        .with_time(ray.time);
+
+        let mut hit = self.object.intersect(&local_ray, interval)?;
+        // transforms keep the side of the surface the ray is on, so the
+        // normal still faces the ray and is_front_face stays valid
+        hit.point = self.transform.point(&hit.point);
+        hit.normal = self.transform.normal(&hit.normal).normalize();
+        hit.ray = *ray;
+        Some(hit)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bbox
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        image::color,
+        materials::{lambertian::Lambertian, material::Materials},
+        math::utils::is_close_to,
+        shapes::sphere::Sphere,
+    };
+
+    fn unit_sphere() -> Arc<Sphere> {
+        Arc::new(Sphere::new(
+            1.0,
+            Vector3f::new(0.0, 0.0, 0.0),
+            Materials::Lambertian(Lambertian::new(color::RED)),
+        ))
+    }
+
+    #[test]
+    fn translated_instances_share_geometry() {
+        let sphere = unit_sphere();
+        let left = Transformed::new(
+            Arc::clone(&sphere),
+            Transform::translation(Vector3f::new(-3.0, 0.0, 0.0)),
+        );
+        let right = Transformed::new(
+            Arc::clone(&sphere),
+            Transform::translation(Vector3f::new(3.0, 0.0, 0.0)),
+        );
+        let interval = Interval::new(0.0, f64::INFINITY);
+        let down = Vector3f::new(0.0, 0.0, -1.0);
+
+        let hit = left
+            .intersect(&Ray::new(Vector3f::new(-3.0, 0.0, 5.0), down), interval)
+            .unwrap();
+        assert!(is_close_to(&hit.time, &4.0));
+        assert_eq!(hit.point, Vector3f::new(-3.0, 0.0, 1.0));
+        assert!(
+            right
+                .intersect(&Ray::new(Vector3f::new(0.0, 0.0, 5.0), down), interval)
+                .is_none()
+        );
+        assert_eq!(Arc::strong_count(&sphere), 3);
+    }
+
+    #[test]
+    fn scaled_normal() {
+        // ellipsoid twice as wide along x
+        let ellipsoid = Transformed::new(
+            unit_sphere(),
+            Transform::scaling(Vector3f::new(2.0, 1.0, 1.0)),
+        );
+        let interval = Interval::new(0.0, f64::INFINITY);
+
+        let ray = Ray::new(Vector3f::new(5.0, 0.0, 0.0), Vector3f::new(-1.0, 0.0, 0.0));
+        let hit = ellipsoid.intersect(&ray, interval).unwrap();
+        assert!(is_close_to(&hit.time, &3.0));
+
+        // at (sqrt 2, sqrt 0.5, 0) the surface normal is along (x / 4, y, 0)
+        let p = Vector3f::new(2.0_f64.sqrt(), 0.5_f64.sqrt(), 0.0);
+        let ray = Ray::new(p * 3.0, -p);
+        let hit = ellipsoid.intersect(&ray, interval).unwrap();
+        let expected = Vector3f::new(p.x / 4.0, p.y, 0.0).normalize();
+        assert_eq!(hit.normal, expected);
+        assert!(hit.is_front_face);
+
+        let bbox = ellipsoid.bounding_box();
+        assert!(is_close_to(&bbox.x.max, &2.0));
+        assert!(is_close_to(&bbox.y.max, &1.0));
+    }
+
+    #[test]
+    fn rotated_bounding_box() {
+        let rotated = Transformed::new(
+            unit_sphere(),
+            Transform::rotation(Vector3f::new(0.0, 0.0, 1.0), 45.0),
+        );
+        let bbox = rotated.bounding_box();
+        assert!(is_close_to(&bbox.x.max, &2.0_f64.sqrt()));
+    }
+}
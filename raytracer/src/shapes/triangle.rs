@@ -0,0 +1,264 @@
+use crate::{
+    geometry::{
+        aabb::{self, Aabb},
+        intersectable::{HitRecord, Intersectable},
+    },
+    interval::Interval,
+    materials::material::Materials,
+    math::{Normal3f, Point3f, Ray, utils::near_zero_vector},
+};
+
+/// Determinants smaller than this mean the ray is parallel to the triangle
+const PARALLEL_EPSILON: f64 = 1e-12;
+
+pub struct Triangle {
+    vertices: [Point3f; 3],
+    /// per vertex normals for smooth shading
+    normals: Option<[Normal3f; 3]>,
+    /// per vertex texture coordinates, the barycentrics are used without them
+    uvs: Option<[(f64, f64); 3]>,
+    material: Materials,
+}
+
+impl Triangle {
+    pub fn new(a: Point3f, b: Point3f, c: Point3f, material: Materials) -> Self {
+        Self {
+            vertices: [a, b, c],
+            normals: None,
+            uvs: None,
+            material,
+        }
+    }
+
+    pub fn with_normals(mut self, normals: [Normal3f; 3]) -> Self {
+        self.normals = Some(normals.map(|n| n.normalize()));
+        self
+    }
+
+    pub fn with_uvs(mut self, uvs: [(f64, f64); 3]) -> Self {
+        self.uvs = Some(uvs);
+        self
+    }
+}
+
+/// Möller–Trumbore ray triangle intersection, returns the ray parameter and
+/// the barycentric coordinates (u, v) of vertices b and c
+pub fn intersect_triangle(
+    ray: &Ray,
+    vertices: &[Point3f; 3],
+    interval: Interval,
+) -> Option<(f64, f64, f64)> {
+    let [a, b, c] = vertices;
+    let edge1 = *b - *a;
+    let edge2 = *c - *a;
+
+    let p = ray.direction.cross(&edge2);
+    let det = edge1.dot(&p);
+    if det.abs() < PARALLEL_EPSILON {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+
+    let s = ray.origin - *a;
+    let u = s.dot(&p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = s.cross(&edge1);
+    let v = ray.direction.dot(&q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(&q) * inv_det;
+    if !interval.surrounds(t) {
+        return None;
+    }
+
+    Some((t, u, v))
+}
+
+/// Hit record for a triangle hit at ray parameter t with barycentric (u, v),
+/// interpolating the vertex normals and texture coordinates when given
+pub fn triangle_hit<'a>(
+    ray: &Ray,
+    (t, u, v): (f64, f64, f64),
+    vertices: &[Point3f; 3],
+    normals: Option<[Normal3f; 3]>,
+    uvs: Option<[(f64, f64); 3]>,
+    material: &'a Materials,
+) -> HitRecord<'a> {
+    let barycentric = [1.0 - u - v, u, v];
+    let [a, b, c] = vertices;
+
+    // the side is decided by the winding, vertex normals only shade
+    let geometric = (*b - *a).cross(&(*c - *a)).normalize();
+    let is_front_face = ray.direction.dot(&geometric) < 0.0;
+    let side = if is_front_face { geometric } else { -geometric };
+
+    let shading = match normals {
+        Some([na, nb, nc]) => {
+            let sum = na * barycentric[0] + nb * barycentric[1] + nc * barycentric[2];
+            if near_zero_vector(&sum) {
+                side
+            } else {
+                sum.normalize()
+            }
+        }
+        None => side,
+    };
+    let normal = if shading.dot(&side) < 0.0 {
+        -shading
+    } else {
+        shading
+    };
+
+    let (tex_u, tex_v) = match uvs {
+        Some(uvs) => (
+            uvs[0].0 * barycentric[0] + uvs[1].0 * barycentric[1] + uvs[2].0 * barycentric[2],
+            uvs[0].1 * barycentric[0] + uvs[1].1 * barycentric[1] + uvs[2].1 * barycentric[2],
+        ),
+        None => (u, v),
+    };
+
+    HitRecord {
+        point: ray.at(t),
+        normal,
+        material,
+        time: t,
+        is_front_face,
+        ray: *ray,
+        u: tex_u,
+        v: tex_v,
+        barycentric: Some(barycentric),
+    }
+}
+
+impl Intersectable for Triangle {
+    fn intersect(&self, ray: &Ray, interval: Interval) -> Option<HitRecord<'_>> {
+        let hit = intersect_triangle(ray, &self.vertices, interval)?;
+        Some(triangle_hit(
+            ray,
+            hit,
+            &self.vertices,
+            self.normals,
+            self.uvs,
+            &self.material,
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let [a, b, c] = self.vertices;
+        Aabb::surrounding(&Aabb::from_points(a, b), &Aabb::from_points(c, c)).padded(aabb::PADDING)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        image::color,
+        materials::lambertian::Lambertian,
+        math::{Vector3f, utils::is_close_to},
+    };
+
+    fn triangle() -> Triangle {
+        Triangle::new(
+            Vector3f::new(0.0, 0.0, 0.0),
+            Vector3f::new(1.0, 0.0, 0.0),
+            Vector3f::new(0.0, 1.0, 0.0),
+            Materials::Lambertian(Lambertian::new(color::RED)),
+        )
+    }
+
+    fn ray_at(x: f64, y: f64) -> Ray {
+        Ray::new(Vector3f::new(x, y, 1.0), Vector3f::new(0.0, 0.0, -1.0))
+    }
+
+    const ALL: Interval = Interval::new(0.0, f64::INFINITY);
+
+    #[test]
+    fn hit_inside() {
+        let tri = triangle();
+        let hit = tri.intersect(&ray_at(0.25, 0.5), ALL).unwrap();
+        assert!(is_close_to(&hit.time, &1.0));
+        assert_eq!(hit.normal, Vector3f::new(0.0, 0.0, 1.0));
+        assert!(hit.is_front_face);
+
+        let [w0, w1, w2] = hit.barycentric.unwrap();
+        assert!(is_close_to(&w0, &0.25));
+        assert!(is_close_to(&w1, &0.25));
+        assert!(is_close_to(&w2, &0.5));
+    }
+
+    #[test]
+    fn miss_outside() {
+        assert!(triangle().intersect(&ray_at(0.75, 0.75), ALL).is_none());
+        assert!(triangle().intersect(&ray_at(-0.1, 0.5), ALL).is_none());
+    }
+
+    #[test]
+    fn parallel_ray() {
+        let ray = Ray::new(Vector3f::new(-1.0, 0.2, 0.0), Vector3f::new(1.0, 0.0, 0.0));
+        assert!(triangle().intersect(&ray, ALL).is_none());
+    }
+
+    #[test]
+    fn back_face() {
+        let ray = Ray::new(Vector3f::new(0.2, 0.2, -1.0), Vector3f::new(0.0, 0.0, 1.0));
+        let tri = triangle();
+        let hit = tri.intersect(&ray, ALL).unwrap();
+        assert!(!hit.is_front_face);
+        assert_eq!(hit.normal, Vector3f::new(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn interpolated_normal_and_uv() {
+        let tri = triangle()
+            .with_normals([
+                Vector3f::new(0.0, 0.0, 1.0),
+                Vector3f::new(1.0, 0.0, 1.0),
+                Vector3f::new(0.0, 0.0, 1.0),
+            ])
+            .with_uvs([(0.0, 0.0), (2.0, 0.0), (0.0, 4.0)]);
+
+        let hit = tri.intersect(&ray_at(0.5, 0.25), ALL).unwrap();
+        assert!(hit.normal.x > 0.0);
+        assert!(is_close_to(&hit.normal.norm(), &1.0));
+        assert!(is_close_to(&hit.u, &1.0));
+        assert!(is_close_to(&hit.v, &1.0));
+    }
+
+    #[test]
+    fn leaning_vertex_normals_keep_geometric_side() {
+        let lean = Vector3f::new(1.0, 0.0, -0.2);
+        let tri = triangle().with_normals([lean, lean, lean]);
+        let ray = Ray::new(Vector3f::new(0.0, 0.25, 1.0), Vector3f::new(1.0, 0.0, -4.0));
+
+        let hit = tri.intersect(&ray, ALL).unwrap();
+        assert!(hit.is_front_face);
+        assert!(hit.normal.z > 0.0);
+        assert!(is_close_to(&hit.normal.norm(), &1.0));
+    }
+
+    #[test]
+    fn cancelling_vertex_normals_fall_back_to_face_normal() {
+        let tri = triangle().with_normals([
+            Vector3f::new(1.0, 0.0, 0.0),
+            Vector3f::new(-1.0, 0.0, 0.0),
+            Vector3f::new(-1.0, 0.0, 0.0),
+        ]);
+
+        let hit = tri.intersect(&ray_at(0.25, 0.25), ALL).unwrap();
+        assert!(hit.is_front_face);
+        assert_eq!(hit.normal, Vector3f::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn flat_bounding_box_has_volume() {
+        let bbox = triangle().bounding_box();
+        assert!(bbox.z.size() > 0.0);
+        assert!(bbox.hit(&ray_at(0.25, 0.25), ALL));
+    }
+}
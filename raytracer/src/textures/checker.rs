@@ -0,0 +1,98 @@
+use crate::{
+    image::Color,
+    math::Point3f,
+    textures::texture::{Texture, Textures},
+};
+
+/// Checkerboard of cubes filling space, shapes look carved out of it. A
+/// surface lying on a cube face speckles with both colors
+#[derive(Debug, Clone)]
+pub struct SolidChecker {
+    /// edge length of a cube
+    scale: f64,
+    even: Box<Textures>,
+    odd: Box<Textures>,
+}
+
+impl SolidChecker {
+    pub fn new(scale: f64, even: Textures, odd: Textures) -> Self {
+        Self {
+            scale,
+            even: Box::new(even),
+            odd: Box::new(odd),
+        }
+    }
+}
+
+impl Texture for SolidChecker {
+    fn value(&self, u: f64, v: f64, point: &Point3f) -> Color<f64> {
+        let cell = |x: f64| (x / self.scale).floor() as i64;
+        if (cell(point.x) + cell(point.y) + cell(point.z)).rem_euclid(2) == 0 {
+            self.even.value(u, v, point)
+        } else {
+            self.odd.value(u, v, point)
+        }
+    }
+}
+
+/// Checkerboard painted on the surface coordinates, it follows the surface
+/// however the shape is placed
+#[derive(Debug, Clone)]
+pub struct UvChecker {
+    /// number of squares across u and v from 0 to 1
+    columns: f64,
+    rows: f64,
+    even: Box<Textures>,
+    odd: Box<Textures>,
+}
+
+impl UvChecker {
+    pub fn new(columns: f64, rows: f64, even: Textures, odd: Textures) -> Self {
+        Self {
+            columns,
+            rows,
+            even: Box::new(even),
+            odd: Box::new(odd),
+        }
+    }
+}
+
+impl Texture for UvChecker {
+    fn value(&self, u: f64, v: f64, point: &Point3f) -> Color<f64> {
+        let column = (u * self.columns).floor() as i64;
+        let row = (v * self.rows).floor() as i64;
+        if (column + row).rem_euclid(2) == 0 {
+            self.even.value(u, v, point)
+        } else {
+            self.odd.value(u, v, point)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{image::color, math::Vector3f};
+
+    #[test]
+    fn solid_checker_alternates_in_space() {
+        let checker = SolidChecker::new(0.5, color::WHITE.into(), color::BLACK.into());
+        let at = |x, y, z| checker.value(0.0, 0.0, &Vector3f::new(x, y, z));
+
+        assert_eq!(at(0.1, 0.1, 0.1), color::WHITE);
+        assert_eq!(at(0.6, 0.1, 0.1), color::BLACK);
+        assert_eq!(at(0.6, 0.6, 0.1), color::WHITE);
+        // negative coordinates keep alternating across zero
+        assert_eq!(at(-0.1, 0.1, 0.1), color::BLACK);
+    }
+
+    #[test]
+    fn uv_checker_alternates_on_the_surface() {
+        let checker = UvChecker::new(4.0, 2.0, color::WHITE.into(), color::BLACK.into());
+        let origin = Vector3f::new(0.0, 0.0, 0.0);
+
+        assert_eq!(checker.value(0.1, 0.1, &origin), color::WHITE);
+        assert_eq!(checker.value(0.3, 0.1, &origin), color::BLACK);
+        assert_eq!(checker.value(0.3, 0.6, &origin), color::WHITE);
+    }
+}
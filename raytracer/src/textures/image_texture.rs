@@ -0,0 +1,171 @@
+use std::{io, path::Path, sync::Arc};
+
+use crate::{
+    image::{Color, Framebuffer, ImageFormat, display::srgb_to_linear},
+    math::Point3f,
+    textures::texture::Texture,
+};
+
+/// What happens to surface coordinates outside of [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Wrap {
+    /// the image tiles
+    #[default]
+    Repeat,
+    /// the edge pixels stretch outwards
+    Clamp,
+    /// the image tiles, flipping every other copy so the edges line up
+    Mirror,
+}
+
+impl Wrap {
+    /// pixel index for i on an axis of size pixels
+    fn apply(&self, i: i64, size: i64) -> i64 {
+        match self {
+            Wrap::Repeat => i.rem_euclid(size),
+            Wrap::Clamp => i.clamp(0, size - 1),
+            Wrap::Mirror => {
+                let i = i.rem_euclid(2 * size);
+                if i < size { i } else { 2 * size - 1 - i }
+            }
+        }
+    }
+}
+
+/// Image stretched over the surface coordinates, u = 0 is the left edge and
+/// v = 0 the bottom. Colors are bilinearly filtered between pixel centers
+#[derive(Debug, Clone)]
+pub struct ImageTexture {
+    /// linear colors, shared by all the materials using the image
+    image: Arc<Framebuffer>,
+    wrap: Wrap,
+}
+
+impl ImageTexture {
+    /// image must hold linear colors
+    pub fn new(image: Arc<Framebuffer>) -> Self {
+        Self {
+            image,
+            wrap: Wrap::default(),
+        }
+    }
+
+    /// Read the image at path, 8 bit formats are taken as sRGB encoded and
+    /// converted to linear colors, high dynamic range ones are already linear
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let image = Framebuffer::load(path)?;
+        let is_linear = ImageFormat::from_path(path).is_some_and(|f| f.is_high_dynamic_range());
+        if is_linear {
+            Ok(Self::new(Arc::new(image)))
+        } else {
+            Ok(Self::from_srgb(&image))
+        }
+    }
+
+    /// image holds sRGB encoded colors, as read from 8 bit formats
+    pub fn from_srgb(image: &Framebuffer) -> Self {
+        let pixels = image
+            .pixels()
+            .iter()
+            .map(|c| {
+                Color::new(
+                    srgb_to_linear(c.r),
+                    srgb_to_linear(c.g),
+                    srgb_to_linear(c.b),
+                )
+            })
+            .collect();
+        Self::new(Arc::new(Framebuffer::from_pixels(
+            image.width(),
+            image.height(),
+            pixels,
+        )))
+    }
+
+    pub fn with_wrap(mut self, wrap: Wrap) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    fn pixel(&self, x: i64, y: i64) -> Color<f64> {
+        let width = i64::from(self.image.width());
+        let height = i64::from(self.image.height());
+        self.image.get(
+            self.wrap.apply(x, width) as u16,
+            self.wrap.apply(y, height) as u16,
+        )
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _point: &Point3f) -> Color<f64> {
+        // magenta stands out where an empty image was used
+        if self.image.width() == 0 || self.image.height() == 0 {
+            return Color::new(1.0, 0.0, 1.0);
+        }
+
+        // pixel centers sit at half integer coordinates, rows go top down
+        let x = u * f64::from(self.image.width()) - 0.5;
+        let y = (1.0 - v) * f64::from(self.image.height()) - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = self.pixel(x0, y0) * (1.0 - fx) + self.pixel(x0 + 1, y0) * fx;
+        let bottom = self.pixel(x0, y0 + 1) * (1.0 - fx) + self.pixel(x0 + 1, y0 + 1) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{image::color, math::Vector3f};
+
+    /// black on the left, white on the right
+    fn two_pixels() -> ImageTexture {
+        ImageTexture::new(Arc::new(Framebuffer::from_pixels(
+            2,
+            1,
+            vec![color::BLACK, color::WHITE],
+        )))
+    }
+
+    #[test]
+    fn bilinear_between_pixel_centers() {
+        let texture = two_pixels().with_wrap(Wrap::Clamp);
+        let origin = Vector3f::new(0.0, 0.0, 0.0);
+
+        assert_eq!(texture.value(0.25, 0.5, &origin), color::BLACK);
+        assert_eq!(texture.value(0.75, 0.5, &origin), color::WHITE);
+        assert_eq!(texture.value(0.5, 0.5, &origin), Color::new(0.5, 0.5, 0.5));
+        // clamped past the edges
+        assert_eq!(texture.value(1.5, 0.5, &origin), color::WHITE);
+    }
+
+    #[test]
+    fn wrap_modes() {
+        let origin = Vector3f::new(0.0, 0.0, 0.0);
+        // the center of the first pixel one tile to the right
+        let repeat = two_pixels().value(1.25, 0.5, &origin);
+        let mirror = two_pixels()
+            .with_wrap(Wrap::Mirror)
+            .value(1.25, 0.5, &origin);
+
+        assert_eq!(repeat, color::BLACK);
+        assert_eq!(mirror, color::WHITE);
+        assert_eq!(Wrap::Mirror.apply(-1, 4), 0);
+        assert_eq!(Wrap::Repeat.apply(-1, 4), 3);
+    }
+
+    #[test]
+    fn srgb_is_linearized() {
+        let image = Framebuffer::from_pixels(1, 1, vec![Color::new(188.0 / 255.0, 1.0, 0.0)]);
+        let texture = ImageTexture::from_srgb(&image);
+
+        let c = texture.value(0.5, 0.5, &Vector3f::new(0.0, 0.0, 0.0));
+        assert!((c.r - 0.5).abs() < 0.01);
+        assert_eq!((c.g, c.b), (1.0, 0.0));
+    }
+}
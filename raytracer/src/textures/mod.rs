@@ -0,0 +1,5 @@
+pub mod checker;
+pub mod image_texture;
+pub mod procedural;
+pub mod solid;
+pub mod texture;
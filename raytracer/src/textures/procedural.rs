@@ -0,0 +1,181 @@
+use std::sync::Arc;
+
+use crate::{
+    image::Color,
+    math::{Point3f, noise::Perlin},
+    textures::texture::Texture,
+};
+
+/// Octaves summed for the fractal noise of the textures
+const OCTAVES: u32 = 7;
+
+fn mix(a: Color<f64>, b: Color<f64>, t: f64) -> Color<f64> {
+    a * (1.0 - t) + b * t
+}
+
+/// Bands along z bent by turbulence into veins
+#[derive(Debug, Clone)]
+pub struct Marble {
+    noise: Arc<Perlin>,
+    /// frequency of the bands, larger values give more veins
+    scale: f64,
+    base: Color<f64>,
+    vein: Color<f64>,
+}
+
+impl Marble {
+    pub fn new(noise: Arc<Perlin>, scale: f64) -> Self {
+        Self {
+            noise,
+            scale,
+            base: Color::new(0.9, 0.9, 0.88),
+            vein: Color::new(0.15, 0.15, 0.18),
+        }
+    }
+
+    pub fn with_colors(mut self, base: Color<f64>, vein: Color<f64>) -> Self {
+        self.base = base;
+        self.vein = vein;
+        self
+    }
+}
+
+impl Texture for Marble {
+    fn value(&self, _u: f64, _v: f64, point: &Point3f) -> Color<f64> {
+        let p = *point * self.scale;
+        let phase = p.z + 10.0 * self.noise.turbulence(&p, OCTAVES);
+        // veins are the thin troughs of the sine
+        let t = (0.5 * (1.0 + phase.sin())).powf(0.3);
+        mix(self.vein, self.base, t)
+    }
+}
+
+/// Growth rings around the y axis, wobbled by noise
+#[derive(Debug, Clone)]
+pub struct Wood {
+    noise: Arc<Perlin>,
+    /// rings per unit of distance from the axis
+    scale: f64,
+    light: Color<f64>,
+    dark: Color<f64>,
+}
+
+impl Wood {
+    pub fn new(noise: Arc<Perlin>, scale: f64) -> Self {
+        Self {
+            noise,
+            scale,
+            light: Color::new(0.75, 0.52, 0.3),
+            dark: Color::new(0.4, 0.22, 0.1),
+        }
+    }
+
+    pub fn with_colors(mut self, light: Color<f64>, dark: Color<f64>) -> Self {
+        self.light = light;
+        self.dark = dark;
+        self
+    }
+}
+
+impl Texture for Wood {
+    fn value(&self, _u: f64, _v: f64, point: &Point3f) -> Color<f64> {
+        let p = *point * self.scale;
+        let radius = (p.x * p.x + p.z * p.z).sqrt();
+        // the grain is stretched along the trunk
+        let grain = Point3f::new(p.x, p.y * 0.1, p.z);
+        let ring = (radius + 0.6 * self.noise.fbm(&grain, 3)).rem_euclid(1.0);
+        // late wood at the end of each ring is darker
+        mix(self.light, self.dark, ring * ring)
+    }
+}
+
+/// Half width of the density band cloud edges fade over
+const EDGE: f64 = 0.1;
+
+/// Soft patches of cloud against the sky
+#[derive(Debug, Clone)]
+pub struct Clouds {
+    noise: Arc<Perlin>,
+    /// frequency of the cloud patches
+    scale: f64,
+    /// fraction of the sky covered, 0 is clear and 1 overcast
+    coverage: f64,
+    sky: Color<f64>,
+    cloud: Color<f64>,
+}
+
+impl Clouds {
+    pub fn new(noise: Arc<Perlin>, scale: f64) -> Self {
+        Self {
+            noise,
+            scale,
+            coverage: 0.5,
+            sky: Color::new(0.3, 0.5, 0.9),
+            cloud: Color::new(1.0, 1.0, 1.0),
+        }
+    }
+
+    pub fn with_coverage(mut self, coverage: f64) -> Self {
+        self.coverage = coverage.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_colors(mut self, sky: Color<f64>, cloud: Color<f64>) -> Self {
+        self.sky = sky;
+        self.cloud = cloud;
+        self
+    }
+}
+
+impl Texture for Clouds {
+    fn value(&self, _u: f64, _v: f64, point: &Point3f) -> Color<f64> {
+        let density = 0.5 * (1.0 + self.noise.fbm(&(*point * self.scale), OCTAVES));
+        // cloud edges fade over a band of density around the threshold, which
+        // leaves the whole range outside it at no and full coverage
+        let threshold = (1.0 + 2.0 * EDGE) * (1.0 - self.coverage) - EDGE;
+        let t = ((density - threshold) / (2.0 * EDGE) + 0.5).clamp(0.0, 1.0);
+        mix(self.sky, self.cloud, t * t * (3.0 - 2.0 * t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{image::color, math::Vector3f};
+
+    fn noise() -> Arc<Perlin> {
+        Arc::new(Perlin::new(42))
+    }
+
+    fn within(c: Color<f64>, a: Color<f64>, b: Color<f64>) -> bool {
+        let between = |x: f64, a: f64, b: f64| x >= a.min(b) - 1e-9 && x <= a.max(b) + 1e-9;
+        between(c.r, a.r, b.r) && between(c.g, a.g, b.g) && between(c.b, a.b, b.b)
+    }
+
+    #[test]
+    fn colors_stay_between_the_endpoints() {
+        let marble = Marble::new(noise(), 4.0);
+        let wood = Wood::new(noise(), 6.0).with_colors(color::WHITE, color::BLACK);
+        let clouds = Clouds::new(noise(), 2.0).with_colors(color::BLUE, color::WHITE);
+
+        for i in 0..200 {
+            let p = Vector3f::new(f64::from(i) * 0.037, f64::from(i) * -0.021, 0.3);
+            assert!(within(marble.value(0.0, 0.0, &p), marble.base, marble.vein));
+            assert!(within(wood.value(0.0, 0.0, &p), color::WHITE, color::BLACK));
+            assert!(within(
+                clouds.value(0.0, 0.0, &p),
+                color::BLUE,
+                color::WHITE
+            ));
+        }
+    }
+
+    #[test]
+    fn clouds_coverage() {
+        let p = Vector3f::new(0.3, 0.7, 0.1);
+        let clear = Clouds::new(noise(), 2.0).with_coverage(0.0);
+        let overcast = Clouds::new(noise(), 2.0).with_coverage(1.0);
+        assert_eq!(clear.value(0.0, 0.0, &p), clear.sky);
+        assert_eq!(overcast.value(0.0, 0.0, &p), overcast.cloud);
+    }
+}
@@ -0,0 +1,19 @@
+use crate::{image::Color, math::Point3f, textures::texture::Texture};
+
+/// Same color everywhere
+#[derive(Debug, Clone)]
+pub struct Solid {
+    color: Color<f64>,
+}
+
+impl Solid {
+    pub fn new(color: Color<f64>) -> Self {
+        Self { color }
+    }
+}
+
+impl Texture for Solid {
+    fn value(&self, _u: f64, _v: f64, _point: &Point3f) -> Color<f64> {
+        self.color
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{
+    image::Color,
+    math::Point3f,
+    textures::{
+        checker::{SolidChecker, UvChecker},
+        image_texture::ImageTexture,
+        procedural::{Clouds, Marble, Wood},
+        solid::Solid,
+    },
+};
+
+pub trait Texture {
+    /// Color at surface coordinates (u, v) of the hit at point
+    fn value(&self, u: f64, v: f64, point: &Point3f) -> Color<f64>;
+}
+
+#[derive(Debug, Clone)]
+pub enum Textures {
+    Solid(Solid),
+    SolidChecker(SolidChecker),
+    UvChecker(UvChecker),
+    Image(ImageTexture),
+    Marble(Marble),
+    Wood(Wood),
+    Clouds(Clouds),
+}
+
+impl Texture for Textures {
+    fn value(&self, u: f64, v: f64, point: &Point3f) -> Color<f64> {
+        match self {
+            Textures::Solid(t) => t.value(u, v, point),
+            Textures::SolidChecker(t) => t.value(u, v, point),
+            Textures::UvChecker(t) => t.value(u, v, point),
+            Textures::Image(t) => t.value(u, v, point),
+            Textures::Marble(t) => t.value(u, v, point),
+            Textures::Wood(t) => t.value(u, v, point),
+            Textures::Clouds(t) => t.value(u, v, point),
+        }
+    }
+}
+
+impl From<Color<f64>> for Textures {
+    fn from(color: Color<f64>) -> Self {
+        Textures::Solid(Solid::new(color))
+    }
+}